@@ -0,0 +1,177 @@
+use crate::domain::broadcast::models::{BroadcastGroupInfo, BroadcastMember};
+use crate::domain::host::event::{Data, EventData};
+use crate::infrastructure::error::ApiError;
+use serde_json::json;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, RwLock};
+use tauri::{Emitter, Event, EventId, Listener, Window};
+
+/// Members of a group keyed by terminal event id, with their pause flag.
+type Members = Arc<RwLock<BTreeMap<String, bool>>>;
+
+pub struct BroadcastGroup {
+    window: Window,
+    listener_id: EventId,
+    members: Members,
+}
+
+impl BroadcastGroup {
+    /// Listens for `Data::In` on `group_id` and re-emits it on the event id
+    /// of every member that is not paused, where the terminal stream writes
+    /// it to its channel.
+    pub fn new(window: Window, group_id: &str) -> Self {
+        let members: Members = Arc::new(RwLock::new(BTreeMap::new()));
+
+        let cloned_window = window.clone();
+        let cloned_members = Arc::clone(&members);
+        let listener_id = window.listen(group_id, move |event: Event| {
+            let in_data = match serde_json::from_str::<EventData>(event.payload()) {
+                Ok(EventData {
+                    data: Data::In(in_data),
+                }) => in_data,
+                _ => return,
+            };
+
+            let members = match cloned_members.read() {
+                Ok(members) => members,
+                Err(_) => return,
+            };
+            for (event_id, paused) in members.iter() {
+                if *paused {
+                    continue;
+                }
+
+                let data = json!(EventData {
+                    data: Data::In(in_data.clone()),
+                });
+                if let Err(error) = cloned_window.emit_to("main", event_id, data) {
+                    log::error!("Failed to broadcast input to {event_id}: {error}");
+                }
+            }
+        });
+
+        Self {
+            window,
+            listener_id,
+            members,
+        }
+    }
+
+    pub fn add_member(&self, event_id: String) {
+        if let Ok(mut members) = self.members.write() {
+            members.entry(event_id).or_insert(false);
+        }
+    }
+
+    pub fn remove_member(&self, event_id: &str) -> bool {
+        self.members
+            .write()
+            .map(|mut members| members.remove(event_id).is_some())
+            .unwrap_or(false)
+    }
+
+    pub fn set_paused(&self, event_id: &str, paused: bool) -> bool {
+        self.members
+            .write()
+            .map(|mut members| match members.get_mut(event_id) {
+                Some(member) => {
+                    *member = paused;
+                    true
+                }
+                None => false,
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn info(&self, id: &str) -> BroadcastGroupInfo {
+        let members = self
+            .members
+            .read()
+            .map(|members| {
+                members
+                    .iter()
+                    .map(|(event_id, paused)| BroadcastMember {
+                        event_id: event_id.clone(),
+                        paused: *paused,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        BroadcastGroupInfo {
+            id: id.to_string(),
+            members,
+        }
+    }
+
+    fn close(&self) {
+        self.window.unlisten(self.listener_id);
+    }
+}
+
+pub struct BroadcastManager {
+    groups: HashMap<String, BroadcastGroup>,
+    terminals: HashSet<String>,
+}
+
+impl BroadcastManager {
+    pub fn new() -> Self {
+        Self {
+            groups: HashMap::new(),
+            terminals: HashSet::new(),
+        }
+    }
+}
+
+impl BroadcastManager {
+    pub fn add(&mut self, id: String, group: BroadcastGroup) {
+        if let Some(previous) = self.groups.insert(id, group) {
+            previous.close();
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Result<&BroadcastGroup, ApiError> {
+        self.groups.get(id).ok_or(ApiError::NotFound {
+            item: format!("broadcast group {}", id),
+        })
+    }
+
+    pub fn remove(&mut self, id: &str) -> bool {
+        match self.groups.remove(id) {
+            Some(group) => {
+                group.close();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn list(&self) -> Vec<BroadcastGroupInfo> {
+        self.groups
+            .iter()
+            .map(|(id, group)| group.info(id))
+            .collect()
+    }
+
+    pub fn exist(&self, id: &str) -> bool {
+        self.groups.contains_key(id)
+    }
+
+    /// Marks `event_id` as a running terminal stream that groups can bind.
+    pub fn add_terminal(&mut self, event_id: String) {
+        self.terminals.insert(event_id);
+    }
+
+    /// Forgets a terminal that stopped streaming and drops it from every
+    /// group, so input is no longer forwarded to it.
+    pub fn remove_terminal(&mut self, event_id: &str) {
+        self.terminals.remove(event_id);
+        for group in self.groups.values() {
+            group.remove_member(event_id);
+        }
+    }
+
+    pub fn is_terminal(&self, event_id: &str) -> bool {
+        self.terminals.contains(event_id)
+    }
+}
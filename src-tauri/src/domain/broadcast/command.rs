@@ -0,0 +1,146 @@
+use crate::domain::broadcast::broadcast_manager::{BroadcastGroup, BroadcastManager};
+use crate::infrastructure::app::AppData;
+use crate::infrastructure::error::ApiError;
+use crate::infrastructure::response::Response;
+use tauri::{State, Window};
+use tokio::sync::Mutex;
+
+#[tauri::command]
+pub async fn list_broadcast_groups(state: State<'_, Mutex<AppData>>) -> Result<Response, ApiError> {
+    log::debug!("list_broadcast_groups called");
+
+    let broadcast_manager = &state.lock().await.broadcast_manager;
+
+    Ok(Response::from_data(broadcast_manager.list()))
+}
+
+/// Binds the terminals `event_ids` into a group. Input emitted on `group_id`
+/// is then forwarded to every member that is not paused.
+#[tauri::command]
+pub async fn create_broadcast_group(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    group_id: String,
+    event_ids: Vec<String>,
+) -> Result<Response, ApiError> {
+    log::debug!("create_broadcast_group called");
+
+    let app_data = &mut *state.lock().await;
+
+    if app_data.future_manager.exist(&group_id) {
+        return Err(ApiError::InvalidBroadcastGroup {
+            reason: format!("{} is already used by a running stream", group_id),
+        });
+    }
+    for event_id in event_ids.iter() {
+        check_member(&app_data.broadcast_manager, &group_id, event_id)?;
+    }
+
+    let group = BroadcastGroup::new(window, &group_id);
+    for event_id in event_ids {
+        group.add_member(event_id);
+    }
+    let info = group.info(&group_id);
+
+    app_data.broadcast_manager.add(group_id, group);
+
+    Ok(Response::from_data(info))
+}
+
+#[tauri::command]
+pub async fn delete_broadcast_group(
+    state: State<'_, Mutex<AppData>>,
+    group_id: String,
+) -> Result<Response, ApiError> {
+    log::debug!("delete_broadcast_group called");
+
+    let broadcast_manager = &mut state.lock().await.broadcast_manager;
+
+    if !broadcast_manager.remove(&group_id) {
+        return Err(ApiError::NotFound {
+            item: format!("broadcast group {}", group_id),
+        });
+    }
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+pub async fn add_broadcast_member(
+    state: State<'_, Mutex<AppData>>,
+    group_id: String,
+    event_id: String,
+) -> Result<Response, ApiError> {
+    log::debug!("add_broadcast_member called");
+
+    let broadcast_manager = &state.lock().await.broadcast_manager;
+
+    check_member(broadcast_manager, &group_id, &event_id)?;
+
+    let group = broadcast_manager.get(&group_id)?;
+    group.add_member(event_id);
+
+    Ok(Response::from_data(group.info(&group_id)))
+}
+
+#[tauri::command]
+pub async fn remove_broadcast_member(
+    state: State<'_, Mutex<AppData>>,
+    group_id: String,
+    event_id: String,
+) -> Result<Response, ApiError> {
+    log::debug!("remove_broadcast_member called");
+
+    let broadcast_manager = &state.lock().await.broadcast_manager;
+
+    let group = broadcast_manager.get(&group_id)?;
+    if !group.remove_member(&event_id) {
+        return Err(ApiError::NotFound {
+            item: format!("broadcast member {}", event_id),
+        });
+    }
+
+    Ok(Response::from_data(group.info(&group_id)))
+}
+
+#[tauri::command]
+pub async fn set_broadcast_member_paused(
+    state: State<'_, Mutex<AppData>>,
+    group_id: String,
+    event_id: String,
+    paused: bool,
+) -> Result<Response, ApiError> {
+    log::debug!("set_broadcast_member_paused called");
+
+    let broadcast_manager = &state.lock().await.broadcast_manager;
+
+    let group = broadcast_manager.get(&group_id)?;
+    if !group.set_paused(&event_id, paused) {
+        return Err(ApiError::NotFound {
+            item: format!("broadcast member {}", event_id),
+        });
+    }
+
+    Ok(Response::from_data(group.info(&group_id)))
+}
+
+/// Only running terminal streams can join, and a group can neither contain
+/// itself nor another group, as forwarded input would loop.
+fn check_member(
+    broadcast_manager: &BroadcastManager,
+    group_id: &str,
+    event_id: &str,
+) -> Result<(), ApiError> {
+    if event_id == group_id || broadcast_manager.exist(event_id) {
+        return Err(ApiError::InvalidBroadcastGroup {
+            reason: format!("{} cannot be a member of {}", event_id, group_id),
+        });
+    }
+    if !broadcast_manager.is_terminal(event_id) {
+        return Err(ApiError::NotFound {
+            item: format!("terminal {}", event_id),
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,3 @@
+pub mod broadcast_manager;
+pub mod command;
+pub mod models;
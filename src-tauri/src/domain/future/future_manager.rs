@@ -22,6 +22,17 @@ impl FutureManager {
         id
     }
 
+    /// Registers `cancel_token` under `id` unless a future with that id is
+    /// already running. Futures register before they are spawned, as a future
+    /// ending right away aborts its own id.
+    pub fn try_add(&mut self, cancel_token: CancellationToken, id: &str) -> bool {
+        if self.exist(id) {
+            return false;
+        }
+        self.cancel_tokens.insert(id.to_string(), cancel_token);
+        true
+    }
+
     pub fn abort(&mut self, id: &str) {
         if let Some(cancel_token) = self.cancel_tokens.remove(id) {
             cancel_token.cancel();
@@ -0,0 +1,23 @@
+use crate::infrastructure::error::ApiError;
+use russh::keys::agent::client::AgentClient;
+
+#[cfg(unix)]
+pub type LocalAgent = AgentClient<tokio::net::UnixStream>;
+
+#[cfg(windows)]
+pub type LocalAgent = AgentClient<tokio::net::windows::named_pipe::NamedPipeClient>;
+
+/// Connects to the local ssh-agent named by `SSH_AUTH_SOCK`.
+#[cfg(unix)]
+pub async fn connect_agent() -> Result<LocalAgent, ApiError> {
+    Ok(AgentClient::connect_env().await?)
+}
+
+/// Connects to the local ssh-agent named by `SSH_AUTH_SOCK`, falling back to
+/// the pipe of the OpenSSH for Windows agent.
+#[cfg(windows)]
+pub async fn connect_agent() -> Result<LocalAgent, ApiError> {
+    let pipe = std::env::var("SSH_AUTH_SOCK").unwrap_or(r"\\.\pipe\openssh-ssh-agent".to_string());
+
+    Ok(AgentClient::connect_named_pipe(pipe).await?)
+}
@@ -1,26 +1,48 @@
-use super::models::Credential;
-use crate::domain::host::event::{AuthMethod, Data, EventData, EventEmitter, StatusType};
-use crate::domain::host::models::Host;
+use super::models::{AuthCredential, Credential};
+use crate::domain::host::event::{
+    AuthMethod, Data, EventData, EventEmitter, SessionExit, StatusType,
+};
+use crate::domain::host::models::{
+    ConnectionSettings, ExecOutput, Host, HostExecResult, HostExecStatus, StartupSettings,
+    TerminalSettings,
+};
 use crate::domain::host::session_manager::SessionManager;
+use crate::domain::host::sftp_manager::{transfer, SftpConnection};
+use crate::domain::host::socks::SocksRequest;
+use crate::domain::recording::models::{RecordingHeader, RECORDING_VERSION};
+use crate::domain::recording::recorder::{recording_path, Recorder};
+use crate::domain::session_log::logger::{session_logs_directory, unix_now, SessionLogger};
+use crate::domain::session_log::models::SessionLogHeader;
+use crate::domain::setting::models::Settings;
 use crate::domain::store::r#enum::StoreKey;
 use crate::infrastructure::app::AppData;
 use crate::infrastructure::error::ApiError;
 use crate::infrastructure::response::Response;
 use log;
+use nanoid::nanoid;
 use russh::client::Msg;
-use russh::{Channel, ChannelMsg, Error};
+use russh::{Channel, ChannelMsg, Error, Sig};
 use serde_json::json;
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use std::time::Duration;
-use tauri::{Event, Listener, State, Window};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{Event, Listener, Manager, State, Window};
 use tokio::io::{self, AsyncWriteExt};
 use tokio::net::TcpListener;
-use tokio::sync::{mpsc, Mutex};
-use tokio::task::JoinHandle;
+use tokio::sync::{mpsc, Mutex, Semaphore};
+use tokio::task::{JoinHandle, JoinSet};
 use tokio::time::sleep;
 use tokio_util::bytes::Bytes;
 use tokio_util::sync::CancellationToken;
 
+const RECONNECT_INITIAL_DELAY: Duration = Duration::from_secs(1);
+
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+const DEFAULT_EXEC_TIMEOUT: Duration = Duration::from_secs(60);
+
+const DEFAULT_BATCH_PARALLELISM: usize = 10;
+
 #[tauri::command]
 pub async fn list_hosts(state: State<'_, Mutex<AppData>>) -> Result<Response, ApiError> {
     log::debug!("list_hosts called");
@@ -39,11 +61,36 @@ pub async fn add_host(
     address: String,
     port: u32,
     credential: Credential,
+    jump_host_refs: Option<Vec<String>>,
+    auth_methods: Option<Vec<AuthMethod>>,
+    connection_settings: Option<ConnectionSettings>,
+    terminal_settings: Option<TerminalSettings>,
+    startup_settings: Option<StartupSettings>,
+    forward_agent: Option<bool>,
+    session_logging: Option<bool>,
 ) -> Result<Response, ApiError> {
     log::debug!("add_host called");
     let store_manager = &state.lock().await.store_manager;
 
-    let host = Host::new(Some(label), address, port, credential, None);
+    let connection_settings = connection_settings.unwrap_or_default();
+    connection_settings.client_config()?;
+    let terminal_settings = terminal_settings.unwrap_or_default();
+    terminal_settings.terminal_modes()?;
+
+    let host = Host::new(
+        Some(label),
+        address,
+        port,
+        credential,
+        None,
+        jump_host_refs.unwrap_or_default(),
+        auth_methods.unwrap_or_default(),
+        connection_settings,
+        terminal_settings,
+        startup_settings.unwrap_or_default(),
+        forward_agent.unwrap_or(false),
+        session_logging.unwrap_or(false),
+    );
     let mut hosts = store_manager.get_data::<Vec<Host>>(StoreKey::Hosts)?;
 
     hosts.push(host.clone());
@@ -62,6 +109,13 @@ pub async fn update_host(
     address: String,
     port: u32,
     credential: Credential,
+    jump_host_refs: Option<Vec<String>>,
+    auth_methods: Option<Vec<AuthMethod>>,
+    connection_settings: Option<ConnectionSettings>,
+    terminal_settings: Option<TerminalSettings>,
+    startup_settings: Option<StartupSettings>,
+    forward_agent: Option<bool>,
+    session_logging: Option<bool>,
 ) -> Result<Response, ApiError> {
     log::debug!("update_host called");
 
@@ -74,6 +128,29 @@ pub async fn update_host(
         host.address = address;
         host.port = port;
         host.credential = credential;
+        if let Some(jump_host_refs) = jump_host_refs {
+            host.jump_host_refs = jump_host_refs;
+        }
+        if let Some(auth_methods) = auth_methods {
+            host.auth_methods = auth_methods;
+        }
+        if let Some(connection_settings) = connection_settings {
+            connection_settings.client_config()?;
+            host.connection_settings = connection_settings;
+        }
+        if let Some(terminal_settings) = terminal_settings {
+            terminal_settings.terminal_modes()?;
+            host.terminal_settings = terminal_settings;
+        }
+        if let Some(startup_settings) = startup_settings {
+            host.startup_settings = startup_settings;
+        }
+        if let Some(forward_agent) = forward_agent {
+            host.forward_agent = forward_agent;
+        }
+        if let Some(session_logging) = session_logging {
+            host.session_logging = session_logging;
+        }
         host.clone()
     } else {
         return Err(ApiError::NotFound {
@@ -88,25 +165,31 @@ pub async fn update_host(
 
 #[tauri::command]
 pub async fn update_host_fingerprint(
+    window: Window,
     state: State<'_, Mutex<AppData>>,
     id: String,
     fingerprint: String,
+    event_id: String,
 ) -> Result<Response, ApiError> {
     log::debug!("update_host_fingerprint called");
 
     let store_manager = &state.lock().await.store_manager;
 
-    let mut hosts = store_manager.get_data::<Vec<Host>>(StoreKey::Hosts)?;
-
-    if let Some(host) = hosts.iter_mut().find(|host| host.id == id) {
-        host.fingerprint = Some(fingerprint);
-    } else {
+    if store_manager
+        .get_item::<Host>(StoreKey::Hosts, &id)?
+        .is_none()
+    {
         return Err(ApiError::NotFound {
             item: format!("hostId {}", id),
         });
     }
 
-    store_manager.update_data(StoreKey::Hosts, hosts)?;
+    // The handshake waiting on `event_id` records the key in the known hosts
+    // store, provided `fingerprint` is the one of the key it presented.
+    EventEmitter::new(window, event_id)
+        .emit(Data::HostKeyTrusted((id, fingerprint)))
+        .await?;
+
     Ok(Response::new_ok_message())
 }
 
@@ -129,6 +212,11 @@ pub async fn delete_host(
         });
     }
 
+    for host in hosts.iter_mut() {
+        host.jump_host_refs
+            .retain(|jump_host_ref| jump_host_ref != &id);
+    }
+
     store_manager.update_data(StoreKey::Hosts, hosts)?;
 
     Ok(Response::new_ok_message())
@@ -140,18 +228,13 @@ pub async fn start_terminal_stream(
     state: State<'_, Mutex<AppData>>,
     host_id: String,
     event_id: String,
+    cols: Option<u32>,
+    rows: Option<u32>,
+    record: Option<bool>,
 ) -> Result<Response, ApiError> {
     log::debug!("start_terminal_stream called");
 
-    {
-        let future_manager = &state.lock().await.future_manager;
-
-        if future_manager.exist(&event_id) {
-            return Ok(Response::new_ok_message());
-        }
-    }
-
-    let (host, username, password, private_key_content) = {
+    let (host, credential, jump_hosts) = {
         let store_manager = &state.lock().await.store_manager;
 
         let host = if let Some(host) = store_manager.get_item::<Host>(StoreKey::Hosts, &host_id)? {
@@ -159,11 +242,34 @@ pub async fn start_terminal_stream(
         } else {
             return Err(ApiError::NotFound { item: host_id });
         };
-        let credentials = host.get_credential(store_manager)?;
+        let credential = host.get_credential(store_manager)?;
+        let jump_hosts = host.get_jump_hosts(store_manager)?;
+
+        (host, credential, jump_hosts)
+    };
+
+    let session_log_settings = if host.session_logging {
+        let store_manager = &state.lock().await.store_manager;
+        let settings = store_manager.get_data::<Settings>(StoreKey::Settings)?;
+        let directory =
+            session_logs_directory(window.app_handle(), &settings.session_log_settings)?;
 
-        (host, credentials.0, credentials.1, credentials.2)
+        Some((directory, settings.session_log_settings))
+    } else {
+        None
     };
 
+    let cancel_token = CancellationToken::new();
+    let cloned_cancel_token = cancel_token.clone();
+
+    {
+        let app_data = &mut *state.lock().await;
+        if !app_data.future_manager.try_add(cancel_token, &event_id) {
+            return Ok(Response::new_ok_message());
+        }
+        app_data.broadcast_manager.add_terminal(event_id.clone());
+    }
+
     let (tx, mut rx) = mpsc::channel::<Data>(1024);
 
     let cloned_tx = tx.clone();
@@ -172,16 +278,18 @@ pub async fn start_terminal_stream(
         let _ = cloned_tx.try_send(event_data.data);
     });
 
-    let cancel_token = CancellationToken::new();
-    let cloned_cancel_token = cancel_token.clone();
-
     let event_emitter = Arc::new(EventEmitter::new(window.clone(), event_id.clone()));
 
-    let mut session_manager = SessionManager::new(Arc::clone(&event_emitter), &host);
+    let store_manager = state.lock().await.store_manager.clone();
+    let mut session_manager = SessionManager::new(Arc::clone(&event_emitter), &host, store_manager);
+    session_manager.set_jump_hosts(jump_hosts);
 
-    let _handler: JoinHandle<Result<(), ApiError>> = tokio::spawn(async move {
+    let cloned_window = window.clone();
+    let cloned_event_id = event_id.clone();
+    let handler: JoinHandle<Result<(), ApiError>> = tokio::spawn(async move {
         sleep(Duration::from_millis(100)).await;
 
+        session_manager.check_certificates(&credential).await?;
         log::debug!("Trying to connect to {}:{}", &host.address, &host.port);
         event_emitter.emit_status(StatusType::Connecting).await?;
 
@@ -193,6 +301,11 @@ pub async fn start_terminal_stream(
         }
 
         let mut channel: Option<Channel<Msg>> = None;
+        let mut channel_closed = false;
+        let mut session_exit = SessionExit::default();
+        let mut terminal_size: Option<(u32, u32)> = cols.zip(rows);
+        let mut recorder: Option<Recorder> = None;
+        let mut session_logger: Option<SessionLogger> = None;
 
         loop {
             tokio::select! {
@@ -203,19 +316,91 @@ pub async fn start_terminal_stream(
                         None
                     }
                 }, if channel.is_some() => {
-                    if let Some(ChannelMsg::Data { ref data }) = maybe_msg {
-                        event_emitter.emit_out(Bytes::from(data.to_vec())).await?;
+                    match maybe_msg {
+                        Some(ChannelMsg::Data { ref data })
+                        | Some(ChannelMsg::ExtendedData { ref data, .. }) => {
+                            if let Some(ref mut recorder) = recorder {
+                                recorder.output(data).await?;
+                            }
+                            if let Some(ref mut session_logger) = session_logger {
+                                session_logger.output(data).await?;
+                            }
+                            event_emitter.emit_out(Bytes::from(data.to_vec())).await?;
+                        }
+                        Some(ChannelMsg::ExitStatus { exit_status }) => {
+                            session_exit.exit_status = Some(exit_status);
+                            channel_closed = true;
+                        }
+                        Some(ChannelMsg::ExitSignal { signal_name, .. }) => {
+                            session_exit.exit_signal = Some(signal_to_string(signal_name));
+                            channel_closed = true;
+                        }
+                        Some(ChannelMsg::Eof) | Some(ChannelMsg::Close) => {
+                            channel_closed = true;
+                        }
+                        Some(_) => {}
+                        None => {
+                            channel = None;
+                            // A reported exit ends the session even when the server
+                            // drops the connection right after it.
+                            let exited = session_exit.exit_status.is_some()
+                                || session_exit.exit_signal.is_some();
+                            let reconnected = if exited || (channel_closed && !session_manager.is_closed()) {
+                                event_emitter
+                                    .emit_status(StatusType::SessionEnded(session_exit.clone()))
+                                    .await?;
+                                false
+                            } else {
+                                // The channel going away without the server closing
+                                // it means the connection itself was lost.
+                                event_emitter.emit_status(StatusType::Disconnected).await?;
+
+                                let connection_settings = session_manager.connection_settings(&host)?;
+                                connection_settings.auto_reconnect.unwrap_or(false)
+                                    && reconnect(
+                                        &mut session_manager,
+                                        &event_emitter,
+                                        connection_settings.reconnect_attempts(),
+                                        &cloned_cancel_token,
+                                    )
+                                    .await?
+                            };
+
+                            if !reconnected {
+                                if let Some(recorder) = recorder.take() {
+                                    recorder.finish().await?;
+                                }
+                                if let Some(session_logger) = session_logger.take() {
+                                    session_logger.finish(&session_exit).await?;
+                                }
+                                window.unlisten(window_event_id);
+                                window
+                                    .state::<Mutex<AppData>>()
+                                    .lock()
+                                    .await
+                                    .future_manager
+                                    .abort(&cloned_event_id);
+                                return Ok(());
+                            }
+                        }
                     }
                 },
                 Some(data) = rx.recv() => {
                     match data {
                         Data::In(in_data) => {
+                            if let Some(ref mut recorder) = recorder {
+                                recorder.input(&in_data).await?;
+                            }
                             if let Some(ref mut ch) = channel {
                                 ch.make_writer().write_all(&in_data.into_bytes()).await?;
                             }
 
                         },
                         Data::Size(size_data) => {
+                            terminal_size = Some(size_data);
+                            if let Some(ref mut recorder) = recorder {
+                                recorder.resize(size_data.0, size_data.1).await?;
+                            }
                             if let Some(ref mut ch) = channel {
                                 ch.window_change(size_data.0,size_data.1,0,0).await?
                             }
@@ -232,39 +417,82 @@ pub async fn start_terminal_stream(
                         Data::Status(status_type) => {
                             match status_type {
                                 StatusType::SessionCreated => {
-                                    event_emitter.emit_status(StatusType::TryingToAuthenticate(AuthMethod::KeyboardInteractive)).await?;
-                                    let mut auth_res = session_manager.try_authenticate_kbd_interactive(&username, &password).await?;
-
-                                    if !auth_res {
-                                        if let Some(ref password) = password {
-                                            event_emitter.emit_status(StatusType::TryingToAuthenticate(AuthMethod::Password)).await?;
-                                            auth_res = session_manager.try_authenticate_password(&username, password).await?;
-                                        }
-                                    }
-
-                                    if !auth_res {
-                                        if let Some(ref private_key_content) = private_key_content {
-                                            event_emitter.emit_status(StatusType::TryingToAuthenticate(AuthMethod::PublicKey)).await?;
-                                            auth_res = session_manager.try_authenticate_public_key(&username, private_key_content).await?;
-                                        }
-                                    }
-
-                                    if auth_res {
-                                        event_emitter.emit_status(StatusType::AuthSuccess).await?;
-                                    }
-                                    else {
+                                    // Errors are reported like a rejection so the view
+                                    // stops the stream instead of waiting on it.
+                                    if let Err(err) = session_manager.authenticate(&credential).await {
+                                        log::error!("Authentication failed: {err}");
                                         event_emitter.emit_status(StatusType::AuthFailed).await?;
                                     }
-
                                 }
                                 StatusType::AuthSuccess => {
                                     let new_channel = session_manager.channel_open_session().await?;
                                     event_emitter.emit_status(StatusType::ChannelOpened).await?;
 
-                                    new_channel.request_pty(true, "xterm", 0, 0, 0, 0, &[]).await?;
-                                    new_channel.request_shell(true).await?;
+                                    if host.forward_agent {
+                                        new_channel.agent_forward(false).await?;
+                                    }
+
+                                    let terminal_settings = session_manager.terminal_settings(&host)?;
+                                    // Servers only accept variables listed in their
+                                    // `AcceptEnv`, so rejections are not fatal.
+                                    for (name, value) in terminal_settings.environment.iter() {
+                                        new_channel.set_env(false, name, value).await?;
+                                    }
+
+                                    let (cols, rows) = terminal_size.unwrap_or_default();
+                                    new_channel
+                                        .request_pty(
+                                            true,
+                                            terminal_settings.term(),
+                                            cols,
+                                            rows,
+                                            0,
+                                            0,
+                                            &terminal_settings.terminal_modes()?,
+                                        )
+                                        .await?;
+                                    if let Some(command) = host.startup_settings.exec_command() {
+                                        new_channel.exec(true, command).await?;
+                                    } else {
+                                        new_channel.request_shell(true).await?;
+                                        if let Some(input) = host.startup_settings.shell_input() {
+                                            new_channel.make_writer().write_all(input.as_bytes()).await?;
+                                        }
+                                    }
                                     event_emitter.emit_status(StatusType::StartStreaming).await?;
+
+                                    if record.unwrap_or(false) && recorder.is_none() {
+                                        let (width, height) = terminal_size.unwrap_or((80, 24));
+                                        let header = RecordingHeader {
+                                            version: RECORDING_VERSION,
+                                            width,
+                                            height,
+                                            timestamp: SystemTime::now()
+                                                .duration_since(UNIX_EPOCH)
+                                                .map(|duration| duration.as_secs())
+                                                .ok(),
+                                            title: Some(host.label.clone().unwrap_or(host.address.clone())),
+                                            env: BTreeMap::from([(
+                                                "TERM".to_string(),
+                                                terminal_settings.term().to_string(),
+                                            )]),
+                                        };
+                                        let path = recording_path(window.app_handle(), &nanoid!())?;
+                                        recorder = Some(Recorder::create(&path, &header).await?);
+                                    }
+
+                                    if let (Some((directory, settings)), None) = (&session_log_settings, &session_logger) {
+                                        let header = SessionLogHeader {
+                                            host: host.label.clone().unwrap_or(host.address.clone()),
+                                            address: format!("{}:{}", host.address, host.port),
+                                            username: credential.username.clone(),
+                                            started_at: unix_now(),
+                                        };
+                                        session_logger = Some(SessionLogger::create(directory, settings.clone(), header).await?);
+                                    }
                                     channel = Some(new_channel);
+                                    channel_closed = false;
+                                    session_exit = SessionExit::default();
                                 }
                                 _ => {}
                             }
@@ -274,19 +502,426 @@ pub async fn start_terminal_stream(
                 },
                 _ = cloned_cancel_token.cancelled() => {
                     window.unlisten(window_event_id);
+                    if let Some(recorder) = recorder.take() {
+                        recorder.finish().await?;
+                    }
+                    if let Some(session_logger) = session_logger.take() {
+                        session_logger.finish(&session_exit).await?;
+                    }
                     return Ok(())
                 }
             }
         }
     });
 
+    // However the stream ends, its terminal leaves the broadcast groups.
+    tokio::spawn(async move {
+        let _ = handler.await;
+        cloned_window
+            .state::<Mutex<AppData>>()
+            .lock()
+            .await
+            .broadcast_manager
+            .remove_terminal(&event_id);
+    });
+
+    Ok(Response::new_ok_message())
+}
+
+/// Re-dials the host with exponential backoff. Once the session is back, the
+/// `SessionCreated` status drives authentication and PTY setup through the
+/// regular terminal flow on the same event id. Returns whether the session
+/// came back, `false` once the attempts are exhausted or on cancellation.
+///
+/// Dialing does not prompt for the host key, so the key is checked against
+/// the known hosts store only. It was recorded there when the session was
+/// first established; a key that is unknown or changed by now fails the
+/// attempt instead of being trusted silently.
+async fn reconnect(
+    session_manager: &mut SessionManager,
+    event_emitter: &EventEmitter,
+    attempts: u32,
+    cancel_token: &CancellationToken,
+) -> Result<bool, ApiError> {
+    let mut delay = RECONNECT_INITIAL_DELAY;
+
+    for attempt in 1..=attempts {
+        event_emitter
+            .emit_status(StatusType::Reconnecting(attempt))
+            .await?;
+
+        tokio::select! {
+            _ = sleep(delay) => {},
+            _ = cancel_token.cancelled() => return Ok(false),
+        }
+
+        session_manager.reset();
+        let connected = tokio::select! {
+            result = session_manager.connect(false) => result.is_ok(),
+            _ = cancel_token.cancelled() => return Ok(false),
+        };
+        if connected {
+            event_emitter
+                .emit_status(StatusType::SessionCreated)
+                .await?;
+            return Ok(true);
+        }
+
+        delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+    }
+
+    event_emitter
+        .emit_status(StatusType::ConnectionFailed)
+        .await?;
+    Ok(false)
+}
+
+/// Runs `command` on the host without a PTY and returns its output once it
+/// exits, failing with `CommandTimeout` after `timeout` seconds.
+#[tauri::command]
+pub async fn exec_command(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    host_id: String,
+    command: String,
+    timeout: Option<u64>,
+    event_id: Option<String>,
+) -> Result<Response, ApiError> {
+    log::debug!("exec_command called");
+
+    let (host, credential, jump_hosts) = {
+        let store_manager = &state.lock().await.store_manager;
+
+        let host = if let Some(host) = store_manager.get_item::<Host>(StoreKey::Hosts, &host_id)? {
+            host
+        } else {
+            return Err(ApiError::NotFound { item: host_id });
+        };
+        let credential = host.get_credential(store_manager)?;
+        let jump_hosts = host.get_jump_hosts(store_manager)?;
+
+        (host, credential, jump_hosts)
+    };
+
+    let event_emitter = Arc::new(EventEmitter::new(
+        window.clone(),
+        event_id.unwrap_or(nanoid!()),
+    ));
+
+    let store_manager = state.lock().await.store_manager.clone();
+    let mut session_manager = SessionManager::new(Arc::clone(&event_emitter), &host, store_manager);
+    session_manager.set_jump_hosts(jump_hosts);
+
+    let output = exec_on_host(
+        &mut session_manager,
+        &credential,
+        &command,
+        exec_timeout(timeout),
+    )
+    .await?;
+
+    Ok(Response::from_data(output))
+}
+
+/// Runs `command` on every host of `host_ids`, at most `parallelism` at a
+/// time, emitting a `HostExecResult` on `event_id` when each host starts and
+/// finishes. A host that cannot be resolved fails on its own without stopping
+/// the others. The batch can be stopped with `stop_future`.
+///
+/// Hosts carry no tags or groups, so a selection is always a list of host ids.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_batch_exec(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    host_ids: Vec<String>,
+    event_id: String,
+    command: String,
+    parallelism: Option<usize>,
+    timeout: Option<u64>,
+) -> Result<Response, ApiError> {
+    log::debug!("start_batch_exec called");
+
+    let store_manager = state.lock().await.store_manager.clone();
+
+    let cancel_token = CancellationToken::new();
+    let cloned_cancel_token = cancel_token.clone();
+
+    if !state
+        .lock()
+        .await
+        .future_manager
+        .try_add(cancel_token, &event_id)
+    {
+        return Ok(Response::new_ok_message());
+    }
+
+    let event_emitter = Arc::new(EventEmitter::new(window.clone(), event_id.clone()));
+    let semaphore = Arc::new(Semaphore::new(
+        parallelism
+            .filter(|parallelism| *parallelism > 0)
+            .unwrap_or(DEFAULT_BATCH_PARALLELISM),
+    ));
+    let timeout = exec_timeout(timeout);
+
+    let cloned_event_id = event_id.clone();
+    let _handler: JoinHandle<Result<(), ApiError>> = tokio::spawn(async move {
+        let mut tasks = JoinSet::new();
+
+        for host_id in host_ids {
+            let event_emitter = Arc::clone(&event_emitter);
+            let semaphore = Arc::clone(&semaphore);
+            let command = command.clone();
+            let store_manager = store_manager.clone();
+            // Connection statuses of each host go to their own channel so
+            // that the batch channel only carries results.
+            let host_event_emitter = Arc::new(EventEmitter::new(
+                window.clone(),
+                format!("{}-{}", cloned_event_id, host_id),
+            ));
+
+            tasks.spawn(async move {
+                let _permit = semaphore
+                    .acquire_owned()
+                    .await
+                    .map_err(|error| ApiError::Boxed(Box::new(error)))?;
+
+                event_emitter
+                    .emit(Data::HostExecResult(HostExecResult {
+                        host_id: host_id.clone(),
+                        status: HostExecStatus::Running,
+                        output: None,
+                        error: None,
+                    }))
+                    .await?;
+
+                let result = async {
+                    let host = store_manager
+                        .get_item::<Host>(StoreKey::Hosts, &host_id)?
+                        .ok_or_else(|| ApiError::NotFound {
+                            item: host_id.clone(),
+                        })?;
+                    let credential = host.get_credential(&store_manager)?;
+                    let jump_hosts = host.get_jump_hosts(&store_manager)?;
+
+                    let mut session_manager =
+                        SessionManager::new(host_event_emitter, &host, store_manager);
+                    session_manager.set_jump_hosts(jump_hosts);
+
+                    exec_on_host(&mut session_manager, &credential, &command, timeout).await
+                }
+                .await;
+
+                let (status, output, error) = match result {
+                    Ok(output) => (HostExecStatus::Completed, Some(output), None),
+                    Err(error) => (HostExecStatus::Failed, None, Some(error.to_string())),
+                };
+                event_emitter
+                    .emit(Data::HostExecResult(HostExecResult {
+                        host_id,
+                        status,
+                        output,
+                        error,
+                    }))
+                    .await
+            });
+        }
+
+        // Dropping the join set on cancellation aborts the remaining hosts.
+        loop {
+            tokio::select! {
+                joined = tasks.join_next() => {
+                    match joined {
+                        Some(Ok(Err(error))) => log::error!("Batch execution failed: {error}"),
+                        Some(Err(error)) => log::error!("Batch execution task failed: {error}"),
+                        Some(Ok(Ok(()))) => {}
+                        None => break,
+                    }
+                },
+                _ = cloned_cancel_token.cancelled() => return Ok(()),
+            }
+        }
+
+        event_emitter
+            .emit_status(StatusType::BatchCompleted)
+            .await?;
+        window
+            .state::<Mutex<AppData>>()
+            .lock()
+            .await
+            .future_manager
+            .abort(&cloned_event_id);
+
+        Ok(())
+    });
+
+    Ok(Response::new_ok_message())
+}
+
+fn exec_timeout(timeout: Option<u64>) -> Duration {
+    timeout
+        .filter(|seconds| *seconds > 0)
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_EXEC_TIMEOUT)
+}
+
+/// Connects without prompting for unknown host keys, authenticates and runs
+/// `command`, failing with `CommandTimeout` once `timeout` has elapsed.
+async fn exec_on_host(
+    session_manager: &mut SessionManager,
+    credential: &AuthCredential,
+    command: &str,
+    timeout: Duration,
+) -> Result<ExecOutput, ApiError> {
+    session_manager.check_certificates(credential).await?;
+    tokio::time::timeout(timeout, async {
+        session_manager.connect(false).await?;
+        if !session_manager.authenticate(credential).await? {
+            return Err(ApiError::Russh(Error::NotAuthenticated));
+        }
+
+        run_exec(session_manager, command, None).await
+    })
+    .await
+    .map_err(|_| ApiError::CommandTimeout)?
+}
+
+/// Runs `command` on the host without a PTY, streaming stdout and stderr as
+/// `Out` and `Stderr` events on `event_id` until the command exits or the
+/// stream is stopped with `stop_future`.
+#[tauri::command]
+pub async fn start_exec_stream(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    host_id: String,
+    event_id: String,
+    command: String,
+) -> Result<Response, ApiError> {
+    log::debug!("start_exec_stream called");
+
+    let (host, credential, jump_hosts) = {
+        let store_manager = &state.lock().await.store_manager;
+
+        let host = if let Some(host) = store_manager.get_item::<Host>(StoreKey::Hosts, &host_id)? {
+            host
+        } else {
+            return Err(ApiError::NotFound { item: host_id });
+        };
+        let credential = host.get_credential(store_manager)?;
+        let jump_hosts = host.get_jump_hosts(store_manager)?;
+
+        (host, credential, jump_hosts)
+    };
+
+    let cancel_token = CancellationToken::new();
+    let cloned_cancel_token = cancel_token.clone();
+
+    if !state
+        .lock()
+        .await
+        .future_manager
+        .try_add(cancel_token, &event_id)
     {
-        let future_manager = &mut state.lock().await.future_manager;
-        future_manager.add(cancel_token, Some(event_id.clone()));
+        return Ok(Response::new_ok_message());
     }
+
+    let event_emitter = Arc::new(EventEmitter::new(window.clone(), event_id.clone()));
+
+    let store_manager = state.lock().await.store_manager.clone();
+    let mut session_manager = SessionManager::new(Arc::clone(&event_emitter), &host, store_manager);
+    session_manager.set_jump_hosts(jump_hosts);
+
+    let cloned_event_id = event_id.clone();
+    let _handler: JoinHandle<Result<(), ApiError>> = tokio::spawn(async move {
+        let result = tokio::select! {
+            result = async {
+                session_manager.check_certificates(&credential).await?;
+                log::debug!("Trying to connect to {}:{}", &host.address, &host.port);
+                event_emitter.emit_status(StatusType::Connecting).await?;
+                if session_manager.connect(true).await.is_err() {
+                    event_emitter.emit_status(StatusType::ConnectionFailed).await?;
+                    return Ok(None);
+                }
+                if !session_manager.authenticate(&credential).await? {
+                    return Ok(None);
+                }
+
+                event_emitter.emit_status(StatusType::StartStreaming).await?;
+                run_exec(&mut session_manager, &command, Some(&event_emitter))
+                    .await
+                    .map(Some)
+            } => result,
+            _ = cloned_cancel_token.cancelled() => return Ok(()),
+        };
+
+        if let Ok(Some(output)) = &result {
+            event_emitter
+                .emit_status(StatusType::SessionEnded(output.exit.clone()))
+                .await?;
+        }
+        window
+            .state::<Mutex<AppData>>()
+            .lock()
+            .await
+            .future_manager
+            .abort(&cloned_event_id);
+
+        result.map(|_| ())
+    });
+
     Ok(Response::new_ok_message())
 }
 
+/// Runs `command` on a new session channel until it exits. With an event
+/// emitter the output is streamed instead of collected.
+async fn run_exec(
+    session_manager: &mut SessionManager,
+    command: &str,
+    event_emitter: Option<&EventEmitter>,
+) -> Result<ExecOutput, ApiError> {
+    let mut channel = session_manager.channel_open_session().await?;
+    channel.exec(true, command).await?;
+
+    let mut stdout = vec![];
+    let mut stderr = vec![];
+    let mut exit = SessionExit::default();
+
+    while let Some(msg) = channel.wait().await {
+        match msg {
+            ChannelMsg::Data { ref data } => match event_emitter {
+                Some(event_emitter) => event_emitter.emit_out(Bytes::from(data.to_vec())).await?,
+                None => stdout.extend_from_slice(data),
+            },
+            ChannelMsg::ExtendedData { ref data, .. } => match event_emitter {
+                Some(event_emitter) => {
+                    event_emitter
+                        .emit(Data::Stderr(Bytes::from(data.to_vec())))
+                        .await?
+                }
+                None => stderr.extend_from_slice(data),
+            },
+            ChannelMsg::ExitStatus { exit_status } => exit.exit_status = Some(exit_status),
+            ChannelMsg::ExitSignal { signal_name, .. } => {
+                exit.exit_signal = Some(signal_to_string(signal_name))
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ExecOutput {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        exit,
+    })
+}
+
+fn signal_to_string(signal: Sig) -> String {
+    match signal {
+        Sig::Custom(name) => name,
+        signal => format!("{:?}", signal),
+    }
+}
+
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
 pub async fn start_tunnel_stream(
@@ -308,7 +943,7 @@ pub async fn start_tunnel_stream(
         }
     }
 
-    let (host, username, password, private_key_content) = {
+    let (host, credential, jump_hosts) = {
         let store_manager = &state.lock().await.store_manager;
 
         let host = if let Some(host) = store_manager.get_item::<Host>(StoreKey::Hosts, &host_id)? {
@@ -316,9 +951,10 @@ pub async fn start_tunnel_stream(
         } else {
             return Err(ApiError::NotFound { item: host_id });
         };
-        let credentials = host.get_credential(store_manager)?;
+        let credential = host.get_credential(store_manager)?;
+        let jump_hosts = host.get_jump_hosts(store_manager)?;
 
-        (host, credentials.0, credentials.1, credentials.2)
+        (host, credential, jump_hosts)
     };
 
     let cancel_token = CancellationToken::new();
@@ -326,60 +962,27 @@ pub async fn start_tunnel_stream(
 
     let event_emitter = Arc::new(EventEmitter::new(window.clone(), event_id.clone()));
 
-    let session_manager = Arc::new(Mutex::new(SessionManager::new(
-        Arc::clone(&event_emitter),
-        &host,
-    )));
+    let store_manager = state.lock().await.store_manager.clone();
+    let mut session_manager = SessionManager::new(Arc::clone(&event_emitter), &host, store_manager);
+    session_manager.set_jump_hosts(jump_hosts);
+    let session_manager = Arc::new(Mutex::new(session_manager));
 
     let _handler: JoinHandle<Result<(), ApiError>> = tokio::spawn(async move {
+        session_manager
+            .lock()
+            .await
+            .check_certificates(&credential)
+            .await?;
         log::debug!("Trying to connect to {}:{}", &host.address, &host.port);
         event_emitter.emit_status(StatusType::Connecting).await?;
         session_manager.lock().await.connect(true).await?;
 
-        event_emitter
-            .emit_status(StatusType::TryingToAuthenticate(
-                AuthMethod::KeyboardInteractive,
-            ))
-            .await?;
-
-        let mut auth_res = session_manager
+        session_manager
             .lock()
             .await
-            .try_authenticate_kbd_interactive(&username, &password)
+            .authenticate(&credential)
             .await?;
 
-        if !auth_res {
-            if let Some(ref password) = password {
-                event_emitter
-                    .emit_status(StatusType::TryingToAuthenticate(AuthMethod::Password))
-                    .await?;
-                auth_res = session_manager
-                    .lock()
-                    .await
-                    .try_authenticate_password(&username, password)
-                    .await?;
-            }
-        }
-
-        if !auth_res {
-            if let Some(ref private_key_content) = private_key_content {
-                event_emitter
-                    .emit_status(StatusType::TryingToAuthenticate(AuthMethod::PublicKey))
-                    .await?;
-                auth_res = session_manager
-                    .lock()
-                    .await
-                    .try_authenticate_public_key(&username, private_key_content)
-                    .await?;
-            }
-        }
-
-        if auth_res {
-            event_emitter.emit_status(StatusType::AuthSuccess).await?;
-        } else {
-            event_emitter.emit_status(StatusType::AuthFailed).await?;
-        }
-
         let listener = TcpListener::bind(format!("{local_address}:{local_port}")).await?;
 
         loop {
@@ -427,3 +1030,504 @@ pub async fn start_tunnel_stream(
     }
     Ok(Response::new_ok_message())
 }
+
+#[tauri::command]
+pub async fn start_dynamic_tunnel_stream(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    host_id: String,
+    event_id: String,
+    local_address: String,
+    local_port: u32,
+) -> Result<Response, ApiError> {
+    log::debug!("start_dynamic_tunnel_stream called");
+
+    {
+        let future_manager = &state.lock().await.future_manager;
+        if future_manager.exist(&event_id) {
+            return Ok(Response::new_ok_message());
+        }
+    }
+
+    let (host, credential, jump_hosts) = {
+        let store_manager = &state.lock().await.store_manager;
+
+        let host = if let Some(host) = store_manager.get_item::<Host>(StoreKey::Hosts, &host_id)? {
+            host
+        } else {
+            return Err(ApiError::NotFound { item: host_id });
+        };
+        let credential = host.get_credential(store_manager)?;
+        let jump_hosts = host.get_jump_hosts(store_manager)?;
+
+        (host, credential, jump_hosts)
+    };
+
+    let cancel_token = CancellationToken::new();
+    let cloned_cancel_token = cancel_token.clone();
+
+    let event_emitter = Arc::new(EventEmitter::new(window.clone(), event_id.clone()));
+
+    let store_manager = state.lock().await.store_manager.clone();
+    let mut session_manager = SessionManager::new(Arc::clone(&event_emitter), &host, store_manager);
+    session_manager.set_jump_hosts(jump_hosts);
+    let session_manager = Arc::new(Mutex::new(session_manager));
+
+    let _handler: JoinHandle<Result<(), ApiError>> = tokio::spawn(async move {
+        session_manager
+            .lock()
+            .await
+            .check_certificates(&credential)
+            .await?;
+        log::debug!("Trying to connect to {}:{}", &host.address, &host.port);
+        event_emitter.emit_status(StatusType::Connecting).await?;
+        session_manager.lock().await.connect(true).await?;
+
+        session_manager
+            .lock()
+            .await
+            .authenticate(&credential)
+            .await?;
+
+        let listener = TcpListener::bind(format!("{local_address}:{local_port}")).await?;
+
+        loop {
+            tokio::select! {
+                incoming = listener.accept() => {
+                    if let Ok((mut socket, _)) = incoming {
+                        let cloned_session_manager = Arc::clone(&session_manager);
+                        let cloned_local_address = local_address.clone();
+                        tokio::spawn(async move {
+                            let request = SocksRequest::read(&mut socket).await?;
+
+                            let channel = cloned_session_manager
+                                .lock()
+                                .await
+                                .channel_open_direct_tcpip(
+                                    &request.address,
+                                    request.port,
+                                    &cloned_local_address,
+                                    local_port,
+                                )
+                                .await;
+
+                            let channel = match channel {
+                                Ok(channel) => channel,
+                                Err(err) => {
+                                    request.reply(&mut socket, false).await?;
+                                    return Err(err);
+                                }
+                            };
+                            request.reply(&mut socket, true).await?;
+
+                            let mut stream = channel.into_stream();
+                            io::copy_bidirectional(&mut socket, &mut stream).await?;
+
+                            Ok::<(),ApiError>(())
+                        });
+                    }
+                }
+                _ = cloned_cancel_token.cancelled() =>{
+                    return Ok(())
+                }
+            }
+        }
+    });
+
+    {
+        let future_manager = &mut state.lock().await.future_manager;
+        future_manager.add(cancel_token, Some(event_id.clone()));
+    }
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn start_reverse_tunnel_stream(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    host_id: String,
+    event_id: String,
+    remote_address: String,
+    remote_port: u32,
+    local_address: String,
+    local_port: u32,
+) -> Result<Response, ApiError> {
+    log::debug!("start_reverse_tunnel_stream called");
+
+    let (host, credential, jump_hosts) = {
+        let store_manager = &state.lock().await.store_manager;
+
+        let host = if let Some(host) = store_manager.get_item::<Host>(StoreKey::Hosts, &host_id)? {
+            host
+        } else {
+            return Err(ApiError::NotFound { item: host_id });
+        };
+        let credential = host.get_credential(store_manager)?;
+        let jump_hosts = host.get_jump_hosts(store_manager)?;
+
+        (host, credential, jump_hosts)
+    };
+
+    let cancel_token = CancellationToken::new();
+    let cloned_cancel_token = cancel_token.clone();
+
+    if !state
+        .lock()
+        .await
+        .future_manager
+        .try_add(cancel_token, &event_id)
+    {
+        return Ok(Response::new_ok_message());
+    }
+
+    let event_emitter = Arc::new(EventEmitter::new(window.clone(), event_id.clone()));
+
+    let store_manager = state.lock().await.store_manager.clone();
+    let mut session_manager = SessionManager::new(Arc::clone(&event_emitter), &host, store_manager);
+    session_manager.set_jump_hosts(jump_hosts);
+    session_manager.set_remote_forward(&local_address, local_port);
+
+    let cloned_event_id = event_id.clone();
+    let _handler: JoinHandle<Result<(), ApiError>> = tokio::spawn(async move {
+        let result = async {
+            session_manager.check_certificates(&credential).await?;
+            log::debug!("Trying to connect to {}:{}", &host.address, &host.port);
+            event_emitter.emit_status(StatusType::Connecting).await?;
+            session_manager.connect(true).await?;
+
+            if !session_manager.authenticate(&credential).await? {
+                return Ok(None);
+            }
+
+            session_manager
+                .tcpip_forward(&remote_address, remote_port)
+                .await
+                .map(Some)
+        }
+        .await;
+
+        // A tunnel that never opened releases its id so it can be started again.
+        let bound_port = match result {
+            Ok(Some(bound_port)) => bound_port,
+            result => {
+                window
+                    .state::<Mutex<AppData>>()
+                    .lock()
+                    .await
+                    .future_manager
+                    .abort(&cloned_event_id);
+                return result.map(|_| ());
+            }
+        };
+        event_emitter
+            .emit_status(StatusType::RemoteForwardOpened(bound_port))
+            .await?;
+
+        cloned_cancel_token.cancelled().await;
+
+        session_manager
+            .cancel_tcpip_forward(&remote_address, bound_port)
+            .await?;
+
+        Ok(())
+    });
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+pub async fn start_sftp_session(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    host_id: String,
+    session_id: String,
+) -> Result<Response, ApiError> {
+    log::debug!("start_sftp_session called");
+
+    {
+        let sftp_manager = &state.lock().await.sftp_manager;
+        if sftp_manager.exist(&session_id) {
+            return Ok(Response::new_ok_message());
+        }
+    }
+
+    let (host, credential, jump_hosts) = {
+        let store_manager = &state.lock().await.store_manager;
+
+        let host = if let Some(host) = store_manager.get_item::<Host>(StoreKey::Hosts, &host_id)? {
+            host
+        } else {
+            return Err(ApiError::NotFound { item: host_id });
+        };
+        let credential = host.get_credential(store_manager)?;
+        let jump_hosts = host.get_jump_hosts(store_manager)?;
+
+        (host, credential, jump_hosts)
+    };
+
+    let event_emitter = Arc::new(EventEmitter::new(window.clone(), session_id.clone()));
+
+    let store_manager = state.lock().await.store_manager.clone();
+    let mut session_manager = SessionManager::new(Arc::clone(&event_emitter), &host, store_manager);
+    session_manager.set_jump_hosts(jump_hosts);
+
+    session_manager.check_certificates(&credential).await?;
+    log::debug!("Trying to connect to {}:{}", &host.address, &host.port);
+    event_emitter.emit_status(StatusType::Connecting).await?;
+    session_manager.connect(true).await?;
+
+    if !session_manager.authenticate(&credential).await? {
+        return Err(ApiError::Russh(Error::NotAuthenticated));
+    }
+
+    let sftp = session_manager.open_sftp().await?;
+    event_emitter.emit_status(StatusType::ChannelOpened).await?;
+
+    {
+        let sftp_manager = &mut state.lock().await.sftp_manager;
+        sftp_manager.add(session_id, SftpConnection::new(sftp, session_manager));
+    }
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+pub async fn close_sftp_session(
+    state: State<'_, Mutex<AppData>>,
+    session_id: String,
+) -> Result<Response, ApiError> {
+    log::debug!("close_sftp_session called");
+
+    let connection = {
+        let sftp_manager = &mut state.lock().await.sftp_manager;
+        sftp_manager.remove(&session_id)
+    };
+
+    if let Some(connection) = connection {
+        connection.close().await?;
+    }
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+pub async fn list_sftp_directory(
+    state: State<'_, Mutex<AppData>>,
+    session_id: String,
+    path: String,
+) -> Result<Response, ApiError> {
+    log::debug!("list_sftp_directory called");
+
+    let connection = state.lock().await.sftp_manager.get(&session_id)?;
+
+    let entries = connection.list_directory(&path).await?;
+
+    Ok(Response::from_data(entries))
+}
+
+#[tauri::command]
+pub async fn stat_sftp_path(
+    state: State<'_, Mutex<AppData>>,
+    session_id: String,
+    path: String,
+) -> Result<Response, ApiError> {
+    log::debug!("stat_sftp_path called");
+
+    let connection = state.lock().await.sftp_manager.get(&session_id)?;
+
+    let entry = connection.stat(&path).await?;
+
+    Ok(Response::from_data(entry))
+}
+
+#[tauri::command]
+pub async fn create_sftp_directory(
+    state: State<'_, Mutex<AppData>>,
+    session_id: String,
+    path: String,
+) -> Result<Response, ApiError> {
+    log::debug!("create_sftp_directory called");
+
+    let connection = state.lock().await.sftp_manager.get(&session_id)?;
+
+    connection.create_directory(&path).await?;
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+pub async fn rename_sftp_path(
+    state: State<'_, Mutex<AppData>>,
+    session_id: String,
+    old_path: String,
+    new_path: String,
+) -> Result<Response, ApiError> {
+    log::debug!("rename_sftp_path called");
+
+    let connection = state.lock().await.sftp_manager.get(&session_id)?;
+
+    connection.rename(&old_path, &new_path).await?;
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+pub async fn delete_sftp_path(
+    state: State<'_, Mutex<AppData>>,
+    session_id: String,
+    path: String,
+) -> Result<Response, ApiError> {
+    log::debug!("delete_sftp_path called");
+
+    let connection = state.lock().await.sftp_manager.get(&session_id)?;
+
+    connection.delete(&path).await?;
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn read_sftp_file(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    session_id: String,
+    event_id: String,
+    remote_path: String,
+    local_path: String,
+) -> Result<Response, ApiError> {
+    log::debug!("read_sftp_file called");
+
+    let cancel_token = CancellationToken::new();
+    let cloned_cancel_token = cancel_token.clone();
+
+    let connection = {
+        let app_data = &mut *state.lock().await;
+        let connection = app_data.sftp_manager.get(&session_id)?;
+        if !app_data.future_manager.try_add(cancel_token, &event_id) {
+            return Ok(Response::new_ok_message());
+        }
+        connection
+    };
+
+    let event_emitter = EventEmitter::new(window.clone(), event_id.clone());
+    let cloned_event_id = event_id.clone();
+
+    let _handler: JoinHandle<Result<(), ApiError>> = tokio::spawn(async move {
+        let mut created = false;
+        let result = async {
+            let total = connection.sftp.metadata(&remote_path).await?.len();
+            let mut remote_file = connection.sftp.open(&remote_path).await?;
+            let mut local_file = tokio::fs::File::create(&local_path).await?;
+            created = true;
+
+            transfer(
+                &mut remote_file,
+                &mut local_file,
+                total,
+                &event_emitter,
+                &cloned_cancel_token,
+            )
+            .await
+        }
+        .await;
+
+        // Do not leave a truncated destination behind.
+        if created && !matches!(result, Ok(true)) {
+            if let Err(err) = tokio::fs::remove_file(&local_path).await {
+                log::warn!("Failed to remove partial file {local_path}: {err}");
+            }
+        }
+
+        finish_transfer(&window, &event_emitter, &cloned_event_id, result).await
+    });
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn write_sftp_file(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    session_id: String,
+    event_id: String,
+    local_path: String,
+    remote_path: String,
+) -> Result<Response, ApiError> {
+    log::debug!("write_sftp_file called");
+
+    let cancel_token = CancellationToken::new();
+    let cloned_cancel_token = cancel_token.clone();
+
+    let connection = {
+        let app_data = &mut *state.lock().await;
+        let connection = app_data.sftp_manager.get(&session_id)?;
+        if !app_data.future_manager.try_add(cancel_token, &event_id) {
+            return Ok(Response::new_ok_message());
+        }
+        connection
+    };
+
+    let event_emitter = EventEmitter::new(window.clone(), event_id.clone());
+    let cloned_event_id = event_id.clone();
+
+    let _handler: JoinHandle<Result<(), ApiError>> = tokio::spawn(async move {
+        let mut created = false;
+        let result = async {
+            let mut local_file = tokio::fs::File::open(&local_path).await?;
+            let total = local_file.metadata().await?.len();
+            let mut remote_file = connection.sftp.create(&remote_path).await?;
+            created = true;
+
+            transfer(
+                &mut local_file,
+                &mut remote_file,
+                total,
+                &event_emitter,
+                &cloned_cancel_token,
+            )
+            .await
+        }
+        .await;
+
+        // Do not leave a truncated destination behind.
+        if created && !matches!(result, Ok(true)) {
+            if let Err(err) = connection.sftp.remove_file(&remote_path).await {
+                log::warn!("Failed to remove partial file {remote_path}: {err}");
+            }
+        }
+
+        finish_transfer(&window, &event_emitter, &cloned_event_id, result).await
+    });
+
+    Ok(Response::new_ok_message())
+}
+
+async fn finish_transfer(
+    window: &Window,
+    event_emitter: &EventEmitter,
+    event_id: &str,
+    result: Result<bool, ApiError>,
+) -> Result<(), ApiError> {
+    window
+        .state::<Mutex<AppData>>()
+        .lock()
+        .await
+        .future_manager
+        .abort(event_id);
+
+    match result {
+        Ok(true) => {
+            event_emitter
+                .emit_status(StatusType::TransferCompleted)
+                .await
+        }
+        Ok(false) => Ok(()),
+        Err(err) => {
+            log::error!("Transfer {event_id} failed: {err}");
+            event_emitter.emit_status(StatusType::TransferFailed).await
+        }
+    }
+}
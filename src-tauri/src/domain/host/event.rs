@@ -1,15 +1,45 @@
+use crate::domain::host::models::HostExecResult;
 use crate::infrastructure::error::ApiError;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tauri::{Emitter, Window};
+use std::time::Duration;
+use tauri::{Emitter, Event, Listener, Window};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
 use tokio_util::bytes::Bytes;
 
-#[derive(Serialize, Deserialize, Debug)]
+const PROMPT_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 #[serde(tag = "type", content = "data", rename_all = "PascalCase")]
 pub enum AuthMethod {
     Password,
     PublicKey,
     KeyboardInteractive,
+    Agent,
+    Certificate,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyboardInteractivePrompt {
+    pub prompt: String,
+    pub echo: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyboardInteractiveRequest {
+    pub name: String,
+    pub instructions: String,
+    pub prompts: Vec<KeyboardInteractivePrompt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExit {
+    pub exit_status: Option<u32>,
+    pub exit_signal: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -19,12 +49,33 @@ pub enum StatusType {
     Connecting,
     SessionCreated,
     TryingToAuthenticate(AuthMethod),
+    PartialAuthSuccess(AuthMethod),
     AuthSuccess,
     AuthFailed,
     ChannelOpened,
     StartStreaming,
     NewPublicKeyFound(String),
+    HostKeyChanged(String),
+    HostKeyRevoked(String),
     ConnectionFailed,
+    ConnectingToJumpHost(String),
+    JumpHostConnected(String),
+    JumpHostAuthFailed(String),
+    NewJumpHostPublicKeyFound((String, String)),
+    JumpHostKeyChanged((String, String)),
+    JumpHostKeyRevoked((String, String)),
+    RemoteForwardOpened(u32),
+    TransferCompleted,
+    TransferFailed,
+    PassphraseRequired(String),
+    KeyboardInteractivePrompt(KeyboardInteractiveRequest),
+    CertificateExpired(String),
+    Disconnected,
+    Reconnecting(u32),
+    SessionEnded(SessionExit),
+    AgentForwarded,
+    AgentForwardRejected,
+    BatchCompleted,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -40,9 +91,15 @@ pub enum EvenType {
 pub enum Data {
     In(String),
     Out(Bytes),
+    Stderr(Bytes),
     Size((u32, u32)),
     Status(StatusType),
     TrustPublicKey(bool),
+    HostKeyTrusted((String, String)),
+    Progress((u64, u64)),
+    Passphrase(Option<String>),
+    KeyboardInteractiveAnswers(Option<Vec<String>>),
+    HostExecResult(HostExecResult),
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -76,4 +133,104 @@ impl EventEmitter {
     pub async fn emit_out(&self, bytes: Bytes) -> Result<(), ApiError> {
         self.emit(Data::Out(bytes)).await
     }
+
+    pub async fn emit_progress(&self, transferred: u64, total: u64) -> Result<(), ApiError> {
+        self.emit(Data::Progress((transferred, total))).await
+    }
+
+    /// Emits `status` and waits until the user either trusts the key with
+    /// `fingerprint` through `update_host_fingerprint` or rejects it with
+    /// `TrustPublicKey(false)`. Trust given for any other key is ignored.
+    pub async fn wait_for_host_key_trust(
+        &self,
+        host_id: &str,
+        fingerprint: &str,
+        status: StatusType,
+    ) -> Result<bool, ApiError> {
+        let host_id = host_id.to_string();
+        let fingerprint = fingerprint.to_string();
+        let decision = self
+            .wait_for_response(status, move |data| match data {
+                Data::HostKeyTrusted((trusted_host_id, trusted_fingerprint))
+                    if trusted_host_id == host_id && trusted_fingerprint == fingerprint =>
+                {
+                    Some(true)
+                }
+                Data::TrustPublicKey(false) => Some(false),
+                _ => None,
+            })
+            .await?;
+
+        Ok(decision.unwrap_or(false))
+    }
+
+    /// Asks the user for the passphrase of the private key `label`. Returns
+    /// `None` when the prompt is dismissed or left unanswered.
+    pub async fn wait_for_passphrase(&self, label: &str) -> Result<Option<String>, ApiError> {
+        let passphrase =
+            self.wait_for_response(StatusType::PassphraseRequired(label.to_string()), |data| {
+                match data {
+                    Data::Passphrase(passphrase) => Some(passphrase),
+                    _ => None,
+                }
+            })
+            .await?;
+
+        Ok(passphrase.flatten())
+    }
+
+    /// Relays a keyboard-interactive request to the user and waits for one
+    /// answer per prompt. Returns `None` when the prompt is dismissed or left
+    /// unanswered.
+    pub async fn wait_for_kbd_interactive_answers(
+        &self,
+        request: KeyboardInteractiveRequest,
+    ) -> Result<Option<Vec<String>>, ApiError> {
+        let answers = self
+            .wait_for_response(
+                StatusType::KeyboardInteractivePrompt(request),
+                |data| match data {
+                    Data::KeyboardInteractiveAnswers(answers) => Some(answers),
+                    _ => None,
+                },
+            )
+            .await?;
+
+        Ok(answers.flatten())
+    }
+
+    /// Emits `status` and waits for the first event on the channel accepted by
+    /// `filter`. The listener is registered before emitting so that an
+    /// immediate answer cannot be missed.
+    async fn wait_for_response<T, F>(
+        &self,
+        status: StatusType,
+        filter: F,
+    ) -> Result<Option<T>, ApiError>
+    where
+        T: Send + 'static,
+        F: Fn(Data) -> Option<T> + Send + Sync + 'static,
+    {
+        let (tx, mut rx) = mpsc::channel::<T>(1);
+
+        let listener_id = self.window.listen(&self.channel, move |event: Event| {
+            if let Ok(EventData { data }) = serde_json::from_str::<EventData>(event.payload()) {
+                if let Some(response) = filter(data) {
+                    let _ = tx.try_send(response);
+                }
+            }
+        });
+
+        let result = self.emit_status(status).await;
+        let response = if result.is_ok() {
+            timeout(PROMPT_TIMEOUT, rx.recv()).await.ok().flatten()
+        } else {
+            None
+        };
+
+        self.window.unlisten(listener_id);
+        result?;
+
+        Ok(response)
+    }
 }
@@ -1,5 +1,8 @@
+pub mod agent;
 pub mod commands;
 pub mod event;
-mod models;
+pub mod models;
 pub mod session_manager;
+pub mod sftp_manager;
+pub mod socks;
 pub mod ssh_client;
@@ -1,7 +1,16 @@
 use nanoid::nanoid;
+use russh::keys::Algorithm;
+use russh::{cipher, client, compression, kex, mac, Preferred, Pty};
+use russh_sftp::client::fs::Metadata;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::domain::host::event::{AuthMethod, SessionExit};
 use crate::domain::identity::models::Identity;
+use crate::domain::identity::totp::Totp;
 use crate::domain::private_key::models::PrivateKey;
 use crate::domain::store::r#enum::StoreKey;
 use crate::domain::store::store_manager::StoreManager;
@@ -26,6 +35,8 @@ pub struct LocalAuth {
     pub password: Option<String>,
     #[serde(serialize_with = "empty_to_null")]
     pub private_key_ref: Option<String>,
+    #[serde(default)]
+    pub use_agent: bool,
     // #[serde(serialize_with = "empty_to_null")]
     // pub challenges
 }
@@ -41,6 +52,20 @@ pub struct Host {
     pub credential: Credential,
     #[serde(serialize_with = "empty_to_null")]
     pub fingerprint: Option<String>,
+    #[serde(default)]
+    pub jump_host_refs: Vec<String>,
+    #[serde(default)]
+    pub auth_methods: Vec<AuthMethod>,
+    #[serde(default)]
+    pub connection_settings: ConnectionSettings,
+    #[serde(default)]
+    pub terminal_settings: TerminalSettings,
+    #[serde(default)]
+    pub startup_settings: StartupSettings,
+    #[serde(default)]
+    pub forward_agent: bool,
+    #[serde(default)]
+    pub session_logging: bool,
 }
 
 impl Host {
@@ -51,6 +76,13 @@ impl Host {
         port: u32,
         credential: Credential,
         fingerprint: Option<String>,
+        jump_host_refs: Vec<String>,
+        auth_methods: Vec<AuthMethod>,
+        connection_settings: ConnectionSettings,
+        terminal_settings: TerminalSettings,
+        startup_settings: StartupSettings,
+        forward_agent: bool,
+        session_logging: bool,
     ) -> Self {
         Self {
             id: nanoid!(),
@@ -59,36 +91,27 @@ impl Host {
             port,
             credential,
             fingerprint,
+            jump_host_refs,
+            auth_methods,
+            connection_settings,
+            terminal_settings,
+            startup_settings,
+            forward_agent,
+            session_logging,
         }
     }
 }
 
 impl Host {
-    pub fn get_credential(
-        &self,
-        store_manager: &StoreManager,
-    ) -> Result<(String, Option<String>, Option<String>), ApiError> {
-        match &self.credential {
-            Credential::Local(local_auth) => {
-                let private_key_content =
-                    if let Some(ref private_key_ref) = local_auth.private_key_ref {
-                        let private_key = store_manager
-                            .get_item::<PrivateKey>(StoreKey::PrivateKeys, private_key_ref)?;
-                        if let Some(private_key) = private_key {
-                            Some(private_key.content.clone())
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    };
-
-                Ok((
-                    local_auth.username.clone(),
-                    local_auth.password.clone(),
-                    private_key_content,
-                ))
-            }
+    pub fn get_credential(&self, store_manager: &StoreManager) -> Result<AuthCredential, ApiError> {
+        let (username, password, private_key_ref, use_agent, totp) = match &self.credential {
+            Credential::Local(local_auth) => (
+                local_auth.username.clone(),
+                local_auth.password.clone(),
+                local_auth.private_key_ref.clone(),
+                local_auth.use_agent,
+                None,
+            ),
             Credential::Identity(identity_ref) => {
                 let identity = if let Some(identity) =
                     store_manager.get_item::<Identity>(StoreKey::Identities, identity_ref)?
@@ -100,22 +123,52 @@ impl Host {
                     });
                 };
 
-                let private_key_content =
-                    if let Some(ref private_key_ref) = identity.private_key_ref {
-                        let private_key = store_manager
-                            .get_item::<PrivateKey>(StoreKey::PrivateKeys, private_key_ref)?;
-                        if let Some(private_key) = private_key {
-                            Some(private_key.content)
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
-                    };
-
-                Ok((identity.username, identity.password, private_key_content))
+                (
+                    identity.username,
+                    identity.password,
+                    identity.private_key_ref,
+                    identity.use_agent,
+                    identity.totp,
+                )
             }
+        };
+
+        let private_key = if let Some(ref private_key_ref) = private_key_ref {
+            store_manager.get_item::<PrivateKey>(StoreKey::PrivateKeys, private_key_ref)?
+        } else {
+            None
+        };
+
+        Ok(AuthCredential {
+            username,
+            password,
+            private_key,
+            use_agent,
+            totp,
+        })
+    }
+}
+
+impl Host {
+    pub fn get_jump_hosts(&self, store_manager: &StoreManager) -> Result<Vec<JumpHost>, ApiError> {
+        let mut jump_hosts = vec![];
+
+        for jump_host_ref in self.jump_host_refs.iter() {
+            let host = if let Some(host) =
+                store_manager.get_item::<Host>(StoreKey::Hosts, jump_host_ref)?
+            {
+                host
+            } else {
+                return Err(ApiError::NotFound {
+                    item: format!("jump hostId {}", jump_host_ref),
+                });
+            };
+            let credential = host.get_credential(store_manager)?;
+
+            jump_hosts.push(JumpHost { host, credential });
         }
+
+        Ok(jump_hosts)
     }
 }
 
@@ -124,3 +177,387 @@ impl Identifiable for Host {
         &self.id
     }
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecOutput {
+    pub stdout: String,
+    pub stderr: String,
+    #[serde(flatten)]
+    pub exit: SessionExit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum HostExecStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress of one host of a batch execution. `output` is set once the
+/// command has exited, `error` when the host could not run it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HostExecResult {
+    pub host_id: String,
+    pub status: HostExecStatus,
+    pub output: Option<ExecOutput>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteForward {
+    pub local_address: String,
+    pub local_port: u32,
+}
+
+/// Keepalives are on by default so that dead connections are noticed, a
+/// keepalive interval of 0 turns them off.
+const DEFAULT_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+const DEFAULT_RECONNECT_ATTEMPTS: u32 = 5;
+
+/// SSH protocol preferences of a host. Unset fields fall back to the global
+/// defaults from `Settings`, then to the defaults of russh.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionSettings {
+    #[serde(default)]
+    pub kex_algorithms: Vec<String>,
+    #[serde(default)]
+    pub ciphers: Vec<String>,
+    #[serde(default)]
+    pub macs: Vec<String>,
+    #[serde(default)]
+    pub host_key_algorithms: Vec<String>,
+    #[serde(default)]
+    pub compression: Option<bool>,
+    #[serde(default)]
+    pub keepalive_interval: Option<u64>,
+    #[serde(default)]
+    pub keepalive_count_max: Option<usize>,
+    #[serde(default)]
+    pub inactivity_timeout: Option<u64>,
+    #[serde(default)]
+    pub connection_timeout: Option<u64>,
+    #[serde(default)]
+    pub auto_reconnect: Option<bool>,
+    #[serde(default)]
+    pub reconnect_attempts: Option<u32>,
+}
+
+impl ConnectionSettings {
+    pub fn merge(&self, fallback: &ConnectionSettings) -> ConnectionSettings {
+        fn or<T: Clone>(value: &[T], fallback: &[T]) -> Vec<T> {
+            if value.is_empty() {
+                fallback.to_vec()
+            } else {
+                value.to_vec()
+            }
+        }
+
+        ConnectionSettings {
+            kex_algorithms: or(&self.kex_algorithms, &fallback.kex_algorithms),
+            ciphers: or(&self.ciphers, &fallback.ciphers),
+            macs: or(&self.macs, &fallback.macs),
+            host_key_algorithms: or(&self.host_key_algorithms, &fallback.host_key_algorithms),
+            compression: self.compression.or(fallback.compression),
+            keepalive_interval: self.keepalive_interval.or(fallback.keepalive_interval),
+            keepalive_count_max: self.keepalive_count_max.or(fallback.keepalive_count_max),
+            inactivity_timeout: self.inactivity_timeout.or(fallback.inactivity_timeout),
+            connection_timeout: self.connection_timeout.or(fallback.connection_timeout),
+            auto_reconnect: self.auto_reconnect.or(fallback.auto_reconnect),
+            reconnect_attempts: self.reconnect_attempts.or(fallback.reconnect_attempts),
+        }
+    }
+
+    pub fn reconnect_attempts(&self) -> u32 {
+        self.reconnect_attempts
+            .unwrap_or(DEFAULT_RECONNECT_ATTEMPTS)
+    }
+
+    pub fn connection_timeout(&self) -> Option<Duration> {
+        self.connection_timeout
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Maps the preferences onto a russh client configuration, rejecting
+    /// algorithm names russh does not implement.
+    pub fn client_config(&self) -> Result<client::Config, ApiError> {
+        let default = Preferred::default();
+
+        let preferred = Preferred {
+            kex: algorithm_names(&self.kex_algorithms, default.kex, |name| {
+                kex::Name::try_from(name).ok()
+            })?,
+            key: algorithm_names(&self.host_key_algorithms, default.key, |name| {
+                Algorithm::from_str(name).ok()
+            })?,
+            cipher: algorithm_names(&self.ciphers, default.cipher, |name| {
+                cipher::Name::try_from(name).ok()
+            })?,
+            mac: algorithm_names(&self.macs, default.mac, |name| {
+                mac::Name::try_from(name).ok()
+            })?,
+            compression: match self.compression {
+                Some(true) => Cow::Owned(vec![
+                    compression::ZLIB,
+                    compression::ZLIB_LEGACY,
+                    compression::NONE,
+                ]),
+                Some(false) => Cow::Owned(vec![compression::NONE]),
+                None => default.compression,
+            },
+        };
+
+        let mut config = client::Config {
+            preferred,
+            inactivity_timeout: self
+                .inactivity_timeout
+                .filter(|seconds| *seconds > 0)
+                .map(Duration::from_secs),
+            keepalive_interval: match self.keepalive_interval {
+                Some(0) => None,
+                Some(seconds) => Some(Duration::from_secs(seconds)),
+                None => Some(DEFAULT_KEEPALIVE_INTERVAL),
+            },
+            ..Default::default()
+        };
+        if let Some(keepalive_count_max) = self.keepalive_count_max {
+            config.keepalive_max = keepalive_count_max;
+        }
+
+        Ok(config)
+    }
+}
+
+fn algorithm_names<T: Clone>(
+    names: &[String],
+    default: Cow<'static, [T]>,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Cow<'static, [T]>, ApiError> {
+    if names.is_empty() {
+        return Ok(default);
+    }
+
+    names
+        .iter()
+        .map(|name| {
+            parse(name.trim()).ok_or(ApiError::UnsupportedAlgorithm {
+                name: name.to_string(),
+            })
+        })
+        .collect::<Result<Vec<T>, ApiError>>()
+        .map(Cow::Owned)
+}
+
+const DEFAULT_TERM: &str = "xterm";
+
+/// PTY preferences of a host. Unset fields fall back to the global defaults
+/// from `Settings`; modes and environment variables are merged by name with
+/// the host taking precedence.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalSettings {
+    #[serde(default)]
+    pub term: Option<String>,
+    #[serde(default)]
+    pub modes: BTreeMap<String, u32>,
+    #[serde(default)]
+    pub environment: BTreeMap<String, String>,
+}
+
+impl TerminalSettings {
+    pub fn merge(&self, fallback: &TerminalSettings) -> TerminalSettings {
+        let mut modes = fallback.modes.clone();
+        modes.extend(self.modes.clone());
+
+        let mut environment = fallback.environment.clone();
+        environment.extend(self.environment.clone());
+
+        TerminalSettings {
+            term: self
+                .term
+                .clone()
+                .filter(|term| !term.trim().is_empty())
+                .or(fallback.term.clone()),
+            modes,
+            environment,
+        }
+    }
+
+    pub fn term(&self) -> &str {
+        self.term
+            .as_deref()
+            .map(str::trim)
+            .filter(|term| !term.is_empty())
+            .unwrap_or(DEFAULT_TERM)
+    }
+
+    /// Resolves mode names such as `ECHO` or `VINTR` to their opcodes.
+    pub fn terminal_modes(&self) -> Result<Vec<(Pty, u32)>, ApiError> {
+        self.modes
+            .iter()
+            .map(|(name, value)| {
+                (1..u8::MAX)
+                    .filter_map(Pty::from_u8)
+                    .find(|mode| format!("{:?}", mode).eq_ignore_ascii_case(name.trim()))
+                    .map(|mode| (mode, *value))
+                    .ok_or(ApiError::UnsupportedTerminalMode {
+                        name: name.to_string(),
+                    })
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum StartupMode {
+    /// Opens the login shell and types the startup script into it.
+    #[default]
+    Shell,
+    /// Runs the startup script instead of a shell.
+    Exec,
+}
+
+/// What to run once the terminal channel is open.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupSettings {
+    #[serde(default)]
+    pub mode: StartupMode,
+    #[serde(default, serialize_with = "empty_to_null")]
+    pub script: Option<String>,
+    #[serde(default, serialize_with = "empty_to_null")]
+    pub working_directory: Option<String>,
+}
+
+impl StartupSettings {
+    fn script(&self) -> Option<&str> {
+        self.script
+            .as_deref()
+            .map(str::trim)
+            .filter(|script| !script.is_empty())
+    }
+
+    fn change_directory(&self) -> Option<String> {
+        self.working_directory
+            .as_deref()
+            .map(str::trim)
+            .filter(|directory| !directory.is_empty())
+            .map(|directory| format!("cd {}", shell_quote(directory)))
+    }
+
+    /// The command to `exec` instead of a shell, if the host is in exec mode
+    /// and has a script to run.
+    pub fn exec_command(&self) -> Option<String> {
+        if self.mode != StartupMode::Exec {
+            return None;
+        }
+
+        let script = self.script()?;
+        Some(match self.change_directory() {
+            Some(change_directory) => format!("{} && {}", change_directory, script),
+            None => script.to_string(),
+        })
+    }
+
+    /// The input typed into the shell right after it starts.
+    pub fn shell_input(&self) -> Option<String> {
+        let input = self
+            .change_directory()
+            .into_iter()
+            .chain(self.script().map(str::to_string))
+            .map(|line| line + "\n")
+            .collect::<String>();
+
+        Some(input).filter(|input| !input.is_empty())
+    }
+}
+
+/// Wraps `value` in single quotes for a POSIX shell.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// The credential of a host resolved against the identity and private key
+/// stores, ready to be used for authentication.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthCredential {
+    pub username: String,
+    pub password: Option<String>,
+    pub private_key: Option<PrivateKey>,
+    pub use_agent: bool,
+    pub totp: Option<Totp>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JumpHost {
+    pub host: Host,
+    pub credential: AuthCredential,
+}
+
+impl JumpHost {
+    pub fn label(&self) -> String {
+        self.host
+            .label
+            .clone()
+            .filter(|label| !label.is_empty())
+            .unwrap_or(format!("{}:{}", self.host.address, self.host.port))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SftpEntryType {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SftpEntry {
+    pub name: String,
+    pub path: String,
+    pub entry_type: SftpEntryType,
+    pub size: u64,
+    pub permissions: Option<u32>,
+    pub user: Option<String>,
+    pub group: Option<String>,
+    pub modified_at: Option<u32>,
+}
+
+impl SftpEntry {
+    pub fn new(parent: &str, name: &str, metadata: &Metadata) -> Self {
+        let file_type = metadata.file_type();
+        let entry_type = if file_type.is_dir() {
+            SftpEntryType::Directory
+        } else if file_type.is_symlink() {
+            SftpEntryType::Symlink
+        } else if file_type.is_file() {
+            SftpEntryType::File
+        } else {
+            SftpEntryType::Other
+        };
+
+        Self {
+            name: name.to_string(),
+            path: format!("{}/{}", parent.trim_end_matches('/'), name),
+            entry_type,
+            size: metadata.len(),
+            permissions: metadata.permissions,
+            user: metadata.user.clone(),
+            group: metadata.group.clone(),
+            modified_at: metadata.mtime,
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.entry_type == SftpEntryType::Directory
+    }
+}
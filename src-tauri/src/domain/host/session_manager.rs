@@ -1,131 +1,265 @@
-use crate::domain::host::event::EventEmitter;
-use crate::domain::host::models::Host;
+use crate::domain::host::agent::connect_agent;
+use crate::domain::host::event::{
+    AuthMethod, EventEmitter, KeyboardInteractivePrompt, KeyboardInteractiveRequest, StatusType,
+};
+use crate::domain::host::models::{
+    AuthCredential, ConnectionSettings, Host, JumpHost, RemoteForward, TerminalSettings,
+};
 use crate::domain::host::ssh_client::SshClient;
+use crate::domain::private_key::models::{is_certificate_valid, PrivateKey};
+use crate::domain::setting::models::Settings;
+use crate::domain::store::r#enum::StoreKey;
+use crate::domain::store::store_manager::StoreManager;
 use crate::infrastructure::error::ApiError;
-use russh::client::{Handle, KeyboardInteractiveAuthResponse, Msg};
-use russh::keys::key::PrivateKeyWithHashAlg;
-use russh::keys::{decode_secret_key, HashAlg};
-use russh::{client, Channel};
+use russh::client::{AuthResult, Handle, KeyboardInteractiveAuthResponse, Msg};
+use russh::keys::{decode_secret_key, HashAlg, PrivateKey as SecretKey, PrivateKeyWithHashAlg};
+use russh::{client, Channel, MethodKind};
+use russh_sftp::client::SftpSession;
 use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::time::timeout;
+
+const PASSPHRASE_PROMPT_ATTEMPTS: usize = 3;
+
+/// Mirrors the preference order of OpenSSH.
+const DEFAULT_AUTH_METHODS: [AuthMethod; 5] = [
+    AuthMethod::Agent,
+    AuthMethod::Certificate,
+    AuthMethod::PublicKey,
+    AuthMethod::KeyboardInteractive,
+    AuthMethod::Password,
+];
+
+const TOTP_PROMPT_PATTERNS: [&str; 7] = [
+    "verification code",
+    "one-time password",
+    "one time password",
+    "authenticator",
+    "otp code",
+    "otp:",
+    "token code",
+];
 
 pub struct SessionManager {
     event_emitter: Arc<EventEmitter>,
     session: Option<Handle<SshClient>>,
     host: Host,
+    store_manager: StoreManager,
+    remote_forward: Option<RemoteForward>,
+    jump_hosts: Vec<JumpHost>,
+    jump_sessions: Vec<Handle<SshClient>>,
 }
 
 impl SessionManager {
-    pub fn new(event_emitter: Arc<EventEmitter>, host: &Host) -> Self {
+    pub fn new(event_emitter: Arc<EventEmitter>, host: &Host, store_manager: StoreManager) -> Self {
         Self {
             session: None,
             event_emitter,
             host: (*host).clone(),
+            store_manager,
+            remote_forward: None,
+            jump_hosts: vec![],
+            jump_sessions: vec![],
         }
     }
 }
 
 impl SessionManager {
-    pub async fn connect(&mut self, should_check_public_key: bool) -> Result<(), ApiError> {
-        let config = Arc::new(client::Config {
-            ..Default::default()
+    pub fn set_remote_forward(&mut self, local_address: &str, local_port: u32) {
+        self.remote_forward = Some(RemoteForward {
+            local_address: local_address.to_string(),
+            local_port,
         });
+    }
+
+    pub fn set_jump_hosts(&mut self, jump_hosts: Vec<JumpHost>) {
+        self.jump_hosts = jump_hosts;
+        self.jump_sessions.clear();
+    }
+
+    /// Reports expired or unusable certificates of `credential` and of the
+    /// jump hosts before dialing. Authentication skips such certificates.
+    pub async fn check_certificates(&self, credential: &AuthCredential) -> Result<(), ApiError> {
+        let private_keys = std::iter::once(credential)
+            .chain(
+                self.jump_hosts
+                    .iter()
+                    .map(|jump_host| &jump_host.credential),
+            )
+            .filter_map(|credential| credential.private_key.as_ref());
+
+        for private_key in private_keys {
+            match private_key.parse_certificate() {
+                Ok(Some(certificate)) if !is_certificate_valid(&certificate) => {
+                    self.event_emitter
+                        .emit_status(StatusType::CertificateExpired(private_key.label.clone()))
+                        .await?;
+                }
+                Ok(_) => {}
+                Err(err) => log::warn!("Skipping certificate of {}: {}", private_key.label, err),
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn connect(&mut self, should_check_public_key: bool) -> Result<(), ApiError> {
         let ssh_client = SshClient::new(
             self.event_emitter.clone(),
-            self.host.fingerprint.clone(),
+            &self.host,
+            self.store_manager.clone(),
             should_check_public_key,
+            self.remote_forward.clone(),
+            false,
         );
-        if let Ok(session) = client::connect(
-            config,
-            format!("{}:{}", self.host.address, self.host.port),
-            ssh_client.clone(),
+
+        self.connect_jump_hosts(should_check_public_key).await?;
+
+        let connection_settings = self.connection_settings(&self.host)?;
+        let session = open_session(
+            &connection_settings,
+            self.jump_sessions.last(),
+            &self.host,
+            ssh_client,
         )
-        .await
-        {
-            self.session = Some(session);
-        } else {
-            return Err(ApiError::SessionNotFound);
+        .await;
+
+        match session {
+            Ok(session) => self.session = Some(session),
+            Err(ApiError::ConnectionTimeout) => return Err(ApiError::ConnectionTimeout),
+            Err(_) => return Err(ApiError::SessionNotFound),
         }
 
         Ok(())
     }
 
-    pub async fn try_authenticate_kbd_interactive(
-        &mut self,
-        username: &str,
-        password: &Option<String>,
-    ) -> Result<bool, ApiError> {
-        self.connect(false).await?;
+    /// Resolves the protocol preferences of `host` against the global ones.
+    pub fn connection_settings(&self, host: &Host) -> Result<ConnectionSettings, ApiError> {
+        let settings = self
+            .store_manager
+            .get_data::<Settings>(StoreKey::Settings)?;
 
-        if let Some(ref mut session) = self.session {
-            let mut kbd_response = session
-                .authenticate_keyboard_interactive_start(username, None)
-                .await?;
-            loop {
-                let prompts = match kbd_response {
-                    KeyboardInteractiveAuthResponse::Success => {
-                        return Ok(true);
-                    }
-                    KeyboardInteractiveAuthResponse::Failure => {
-                        return Ok(false);
-                    }
-                    KeyboardInteractiveAuthResponse::InfoRequest { ref prompts, .. } => prompts,
-                };
+        Ok(host
+            .connection_settings
+            .merge(&settings.connection_settings))
+    }
 
-                let mut responses: Vec<String> = vec![];
-                for prompt in prompts {
-                    if prompt.prompt == "Password: " {
-                        if let Some(password) = password {
-                            responses.push(password.to_string());
-                        }
-                    }
-                }
+    /// Resolves the PTY preferences of `host` against the global ones.
+    pub fn terminal_settings(&self, host: &Host) -> Result<TerminalSettings, ApiError> {
+        let settings = self
+            .store_manager
+            .get_data::<Settings>(StoreKey::Settings)?;
 
-                kbd_response = session
-                    .authenticate_keyboard_interactive_respond(responses)
-                    .await?;
-            }
-        }
-        Ok(false)
+        Ok(host.terminal_settings.merge(&settings.terminal_settings))
     }
 
-    pub async fn try_authenticate_password(
-        &mut self,
-        username: &str,
-        password: &str,
-    ) -> Result<bool, ApiError> {
-        self.connect(false).await?;
+    /// Drops the session and the jump host chain so that the next `connect`
+    /// dials every hop again.
+    pub fn reset(&mut self) {
+        self.session = None;
+        self.jump_sessions.clear();
+    }
 
-        if let Some(ref mut session) = self.session {
-            let auth_result = session.authenticate_password(username, password).await?;
+    pub fn is_closed(&self) -> bool {
+        self.session
+            .as_ref()
+            .map(|session| session.is_closed())
+            .unwrap_or(true)
+    }
 
-            if auth_result {
-                return Ok(true);
+    /// Establishes the jump host chain once, each hop being reached through a
+    /// direct-tcpip channel opened on the previous one. Unknown hop keys are
+    /// only offered for trust when `should_check_public_key` is set.
+    async fn connect_jump_hosts(&mut self, should_check_public_key: bool) -> Result<(), ApiError> {
+        if self.jump_sessions.len() == self.jump_hosts.len() {
+            return Ok(());
+        }
+        self.jump_sessions.clear();
+
+        for jump_host in self.jump_hosts.iter() {
+            let label = jump_host.label();
+            self.event_emitter
+                .emit_status(StatusType::ConnectingToJumpHost(label.clone()))
+                .await?;
+
+            let ssh_client = SshClient::new(
+                self.event_emitter.clone(),
+                &jump_host.host,
+                self.store_manager.clone(),
+                should_check_public_key,
+                None,
+                true,
+            );
+
+            let connection_settings = self.connection_settings(&jump_host.host)?;
+            let session = open_session(
+                &connection_settings,
+                self.jump_sessions.last(),
+                &jump_host.host,
+                ssh_client,
+            )
+            .await;
+
+            let mut session = match session {
+                Ok(session) => session,
+                Err(_) => {
+                    self.event_emitter
+                        .emit_status(StatusType::ConnectionFailed)
+                        .await?;
+                    self.jump_sessions.clear();
+                    return Err(ApiError::SessionNotFound);
+                }
+            };
+
+            if !authenticate_session(
+                &self.event_emitter,
+                &mut session,
+                &jump_host.credential,
+                &jump_host.host.auth_methods,
+            )
+            .await?
+            {
+                self.event_emitter
+                    .emit_status(StatusType::JumpHostAuthFailed(label))
+                    .await?;
+                self.jump_sessions.clear();
+                return Err(ApiError::Russh(russh::Error::NotAuthenticated));
             }
+
+            self.event_emitter
+                .emit_status(StatusType::JumpHostConnected(label))
+                .await?;
+            self.jump_sessions.push(session);
         }
-        Ok(false)
+
+        Ok(())
     }
 
-    pub async fn try_authenticate_public_key(
-        &mut self,
-        username: &str,
-        content: &str,
-    ) -> Result<bool, ApiError> {
-        self.connect(false).await?;
+    /// Runs the host's authentication strategy on the established session.
+    pub async fn authenticate(&mut self, credential: &AuthCredential) -> Result<bool, ApiError> {
+        let auth_res = if let Some(ref mut session) = self.session {
+            authenticate_session(
+                &self.event_emitter,
+                session,
+                credential,
+                &self.host.auth_methods,
+            )
+            .await?
+        } else {
+            return Err(ApiError::SessionNotFound);
+        };
 
-        if let Some(ref mut session) = self.session {
-            let private_key = decode_secret_key(content, None)?;
-            let auth_result = session
-                .authenticate_publickey(
-                    username,
-                    PrivateKeyWithHashAlg::new(Arc::new(private_key), Some(HashAlg::Sha512))?,
-                )
+        if auth_res {
+            self.event_emitter
+                .emit_status(StatusType::AuthSuccess)
+                .await?;
+        } else {
+            self.event_emitter
+                .emit_status(StatusType::AuthFailed)
                 .await?;
-
-            if auth_result {
-                return Ok(true);
-            }
         }
-        Ok(false)
+
+        Ok(auth_res)
     }
 
     pub async fn channel_open_session(&mut self) -> Result<Channel<Msg>, ApiError> {
@@ -156,4 +290,395 @@ impl SessionManager {
         }
         Err(ApiError::SessionNotFound)
     }
+
+    pub async fn tcpip_forward(&mut self, address: &str, port: u32) -> Result<u32, ApiError> {
+        if let Some(ref mut session) = self.session {
+            let bound_port = session.tcpip_forward(address, port).await?;
+            return Ok(if bound_port == 0 { port } else { bound_port });
+        }
+        Err(ApiError::SessionNotFound)
+    }
+
+    pub async fn cancel_tcpip_forward(&mut self, address: &str, port: u32) -> Result<(), ApiError> {
+        if let Some(ref mut session) = self.session {
+            session.cancel_tcpip_forward(address, port).await?;
+            return Ok(());
+        }
+        Err(ApiError::SessionNotFound)
+    }
+
+    pub async fn open_sftp(&mut self) -> Result<SftpSession, ApiError> {
+        let channel = self.channel_open_session().await?;
+        channel.request_subsystem(true, "sftp").await?;
+
+        Ok(SftpSession::new(channel.into_stream()).await?)
+    }
+}
+
+/// Opens an SSH session to `host`, tunnelled through `jump_session` when given,
+/// within the configured connection timeout.
+async fn open_session(
+    connection_settings: &ConnectionSettings,
+    jump_session: Option<&Handle<SshClient>>,
+    host: &Host,
+    ssh_client: SshClient,
+) -> Result<Handle<SshClient>, ApiError> {
+    let config = Arc::new(connection_settings.client_config()?);
+
+    let session = async {
+        if let Some(jump_session) = jump_session {
+            let channel = jump_session
+                .channel_open_direct_tcpip(host.address.clone(), host.port, "127.0.0.1", 0)
+                .await?;
+            Ok(client::connect_stream(config, channel.into_stream(), ssh_client).await?)
+        } else {
+            let stream = TcpStream::connect(format!("{}:{}", host.address, host.port)).await?;
+            Ok(client::connect_stream(config, stream, ssh_client).await?)
+        }
+    };
+
+    match connection_settings.connection_timeout() {
+        Some(duration) => timeout(duration, session)
+            .await
+            .map_err(|_| ApiError::ConnectionTimeout)?,
+        None => session.await,
+    }
+}
+
+/// Runs `auth_methods` in order on a single session, or the default strategy
+/// when empty. Methods the server no longer advertises, or for which the
+/// credential holds no secret, are skipped. A partial success keeps going so
+/// multi-factor setups such as publickey followed by password succeed.
+async fn authenticate_session(
+    event_emitter: &EventEmitter,
+    session: &mut Handle<SshClient>,
+    credential: &AuthCredential,
+    auth_methods: &[AuthMethod],
+) -> Result<bool, ApiError> {
+    let mut pending = if auth_methods.is_empty() {
+        DEFAULT_AUTH_METHODS.to_vec()
+    } else {
+        auth_methods.to_vec()
+    };
+
+    let mut remaining_methods = match session.authenticate_none(&credential.username).await? {
+        AuthResult::Success => return Ok(true),
+        AuthResult::Failure {
+            remaining_methods, ..
+        } => remaining_methods,
+    };
+
+    let mut secret_key: Option<Option<SecretKey>> = None;
+
+    while let Some(position) = pending
+        .iter()
+        .position(|auth_method| remaining_methods.contains(&method_kind(auth_method)))
+    {
+        let auth_method = pending.remove(position);
+
+        let auth_res = match auth_method {
+            AuthMethod::KeyboardInteractive => {
+                event_emitter
+                    .emit_status(StatusType::TryingToAuthenticate(auth_method))
+                    .await?;
+                authenticate_kbd_interactive(event_emitter, session, credential).await?
+            }
+            AuthMethod::Password => {
+                let password = match credential.password {
+                    Some(ref password) if !password.is_empty() => password,
+                    _ => continue,
+                };
+                event_emitter
+                    .emit_status(StatusType::TryingToAuthenticate(auth_method))
+                    .await?;
+                session
+                    .authenticate_password(&credential.username, password)
+                    .await?
+            }
+            AuthMethod::Agent => {
+                if !credential.use_agent {
+                    continue;
+                }
+                event_emitter
+                    .emit_status(StatusType::TryingToAuthenticate(auth_method))
+                    .await?;
+                match authenticate_agent(session, &credential.username).await? {
+                    Some(auth_res) => auth_res,
+                    None => continue,
+                }
+            }
+            AuthMethod::Certificate => {
+                // Expired certificates were reported before connecting.
+                let (private_key, certificate) = match credential.private_key {
+                    Some(ref private_key) => match private_key.parse_certificate() {
+                        Ok(Some(certificate)) if is_certificate_valid(&certificate) => {
+                            (private_key, certificate)
+                        }
+                        _ => continue,
+                    },
+                    None => continue,
+                };
+                let key = match cached_secret_key(event_emitter, private_key, &mut secret_key).await
+                {
+                    Some(key) => key,
+                    None => continue,
+                };
+
+                event_emitter
+                    .emit_status(StatusType::TryingToAuthenticate(auth_method))
+                    .await?;
+                session
+                    .authenticate_openssh_cert(&credential.username, Arc::new(key), certificate)
+                    .await?
+            }
+            AuthMethod::PublicKey => {
+                let private_key = match credential.private_key {
+                    Some(ref private_key) => private_key,
+                    None => continue,
+                };
+                let key = match cached_secret_key(event_emitter, private_key, &mut secret_key).await
+                {
+                    Some(key) => key,
+                    None => continue,
+                };
+
+                event_emitter
+                    .emit_status(StatusType::TryingToAuthenticate(auth_method))
+                    .await?;
+                let hash_alg = session
+                    .best_supported_rsa_hash()
+                    .await?
+                    .unwrap_or(Some(HashAlg::Sha512));
+                session
+                    .authenticate_publickey(
+                        &credential.username,
+                        PrivateKeyWithHashAlg::new(Arc::new(key), hash_alg),
+                    )
+                    .await?
+            }
+        };
+
+        match auth_res {
+            AuthResult::Success => return Ok(true),
+            AuthResult::Failure {
+                remaining_methods: methods,
+                partial_success,
+            } => {
+                if partial_success {
+                    event_emitter
+                        .emit_status(StatusType::PartialAuthSuccess(auth_method))
+                        .await?;
+                }
+                remaining_methods = methods;
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+fn method_kind(auth_method: &AuthMethod) -> MethodKind {
+    match auth_method {
+        AuthMethod::Password => MethodKind::Password,
+        AuthMethod::KeyboardInteractive => MethodKind::KeyboardInteractive,
+        AuthMethod::PublicKey | AuthMethod::Agent | AuthMethod::Certificate => {
+            MethodKind::PublicKey
+        }
+    }
+}
+
+/// Answers keyboard-interactive requests, filling prompts that match stored
+/// credentials automatically and relaying the remaining ones to the user.
+async fn authenticate_kbd_interactive(
+    event_emitter: &EventEmitter,
+    session: &mut Handle<SshClient>,
+    credential: &AuthCredential,
+) -> Result<AuthResult, ApiError> {
+    let mut cancelled = false;
+    let mut kbd_response = session
+        .authenticate_keyboard_interactive_start(&credential.username, None)
+        .await?;
+    loop {
+        let (name, instructions, prompts) = match kbd_response {
+            KeyboardInteractiveAuthResponse::Success => {
+                return Ok(AuthResult::Success);
+            }
+            KeyboardInteractiveAuthResponse::Failure {
+                remaining_methods,
+                partial_success,
+            } => {
+                return Ok(AuthResult::Failure {
+                    remaining_methods,
+                    partial_success,
+                });
+            }
+            KeyboardInteractiveAuthResponse::InfoRequest {
+                name,
+                instructions,
+                prompts,
+            } => (name, instructions, prompts),
+        };
+
+        let mut responses: Vec<Option<String>> = prompts
+            .iter()
+            .map(|prompt| stored_kbd_interactive_answer(&prompt.prompt, credential))
+            .collect();
+
+        let unanswered: Vec<KeyboardInteractivePrompt> = prompts
+            .iter()
+            .zip(responses.iter())
+            .filter(|(_, response)| response.is_none())
+            .map(|(prompt, _)| KeyboardInteractivePrompt {
+                prompt: prompt.prompt.clone(),
+                echo: prompt.echo,
+            })
+            .collect();
+
+        // A dismissed prompt is answered with empty strings so the server
+        // fails the exchange and the remaining methods can still be tried.
+        if !unanswered.is_empty() && !cancelled {
+            let answers = event_emitter
+                .wait_for_kbd_interactive_answers(KeyboardInteractiveRequest {
+                    name,
+                    instructions,
+                    prompts: unanswered.clone(),
+                })
+                .await?;
+
+            match answers {
+                Some(answers) if answers.len() == unanswered.len() => {
+                    let mut answers = answers.into_iter();
+                    for response in responses.iter_mut().filter(|response| response.is_none()) {
+                        *response = answers.next();
+                    }
+                }
+                _ => cancelled = true,
+            }
+        }
+
+        kbd_response = session
+            .authenticate_keyboard_interactive_respond(
+                responses
+                    .into_iter()
+                    .map(Option::unwrap_or_default)
+                    .collect(),
+            )
+            .await?;
+    }
+}
+
+/// Returns the stored secret for prompts of a known shape, such as the
+/// password prompt of PAM or the verification code prompt of an authenticator
+/// module.
+fn stored_kbd_interactive_answer(prompt: &str, credential: &AuthCredential) -> Option<String> {
+    let prompt = prompt.trim().to_lowercase();
+
+    if let Some(ref totp) = credential.totp {
+        if TOTP_PROMPT_PATTERNS
+            .iter()
+            .any(|pattern| prompt.contains(pattern))
+        {
+            return totp.now().ok().map(|totp_code| totp_code.code);
+        }
+    }
+
+    if prompt.ends_with("password:") && !prompt.contains("new") {
+        return credential
+            .password
+            .clone()
+            .filter(|password| !password.is_empty());
+    }
+    None
+}
+
+/// Offers every identity held by the local ssh-agent in turn. Returns `None`
+/// when the agent is unreachable so the remaining methods still run.
+async fn authenticate_agent(
+    session: &mut Handle<SshClient>,
+    username: &str,
+) -> Result<Option<AuthResult>, ApiError> {
+    let mut agent = match connect_agent().await {
+        Ok(agent) => agent,
+        Err(err) => {
+            log::warn!("ssh-agent is not available: {}", err);
+            return Ok(None);
+        }
+    };
+
+    let hash_alg = session
+        .best_supported_rsa_hash()
+        .await?
+        .unwrap_or(Some(HashAlg::Sha512));
+
+    let mut auth_res = None;
+    for public_key in agent.request_identities().await? {
+        let result = session
+            .authenticate_publickey_with(username, public_key, hash_alg, &mut agent)
+            .await?;
+        match result {
+            AuthResult::Success
+            | AuthResult::Failure {
+                partial_success: true,
+                ..
+            } => return Ok(Some(result)),
+            AuthResult::Failure { .. } => auth_res = Some(result),
+        }
+    }
+    Ok(auth_res)
+}
+
+/// Decodes `private_key` with its stored passphrase, prompting the user when
+/// the key is encrypted and the passphrase is missing or wrong.
+/// Decodes `private_key` once per authentication, `cache` holding the outcome
+/// for the next method. A key that cannot be decoded, for instance because the
+/// passphrase prompt was dismissed, only skips the methods that need it.
+async fn cached_secret_key(
+    event_emitter: &EventEmitter,
+    private_key: &PrivateKey,
+    cache: &mut Option<Option<SecretKey>>,
+) -> Option<SecretKey> {
+    if cache.is_none() {
+        *cache = Some(match decode_private_key(event_emitter, private_key).await {
+            Ok(key) => Some(key),
+            Err(err) => {
+                log::warn!("Skipping private key {}: {}", private_key.label, err);
+                None
+            }
+        });
+    }
+
+    cache.clone().flatten()
+}
+
+async fn decode_private_key(
+    event_emitter: &EventEmitter,
+    private_key: &PrivateKey,
+) -> Result<SecretKey, ApiError> {
+    let mut passphrase = private_key.passphrase.clone().filter(|p| !p.is_empty());
+
+    let mut attempts = 0;
+    loop {
+        match decode_secret_key(&private_key.content, passphrase.as_deref()) {
+            Ok(secret_key) => return Ok(secret_key),
+            Err(russh::keys::Error::KeyIsEncrypted) => {}
+            Err(err) if passphrase.is_none() => return Err(err.into()),
+            Err(_) => log::debug!("Wrong passphrase for private key {}", private_key.label),
+        }
+
+        if attempts == PASSPHRASE_PROMPT_ATTEMPTS {
+            break;
+        }
+        attempts += 1;
+
+        passphrase = event_emitter
+            .wait_for_passphrase(&private_key.label)
+            .await?;
+        if passphrase.is_none() {
+            break;
+        }
+    }
+
+    Err(ApiError::PrivateKeyEncrypted {
+        label: private_key.label.clone(),
+    })
 }
@@ -0,0 +1,148 @@
+use crate::domain::host::event::EventEmitter;
+use crate::domain::host::models::SftpEntry;
+use crate::domain::host::session_manager::SessionManager;
+use crate::infrastructure::error::ApiError;
+use russh_sftp::client::SftpSession;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
+const TRANSFER_CHUNK_SIZE: usize = 32 * 1024;
+
+pub struct SftpConnection {
+    pub sftp: SftpSession,
+    _session_manager: SessionManager,
+}
+
+impl SftpConnection {
+    pub fn new(sftp: SftpSession, session_manager: SessionManager) -> Self {
+        Self {
+            sftp,
+            _session_manager: session_manager,
+        }
+    }
+}
+
+impl SftpConnection {
+    pub async fn list_directory(&self, path: &str) -> Result<Vec<SftpEntry>, ApiError> {
+        let path = self.sftp.canonicalize(path).await?;
+
+        let mut entries = self
+            .sftp
+            .read_dir(&path)
+            .await?
+            .map(|entry| SftpEntry::new(&path, &entry.file_name(), &entry.metadata()))
+            .collect::<Vec<SftpEntry>>();
+
+        entries.sort_by(|a, b| b.is_dir().cmp(&a.is_dir()).then(a.name.cmp(&b.name)));
+
+        Ok(entries)
+    }
+
+    pub async fn stat(&self, path: &str) -> Result<SftpEntry, ApiError> {
+        let path = self.sftp.canonicalize(path).await?;
+        let metadata = self.sftp.metadata(&path).await?;
+
+        let (parent, name) = match path.rsplit_once('/') {
+            Some((parent, name)) => (parent.to_string(), name.to_string()),
+            None => (String::new(), path.clone()),
+        };
+
+        Ok(SftpEntry::new(&parent, &name, &metadata))
+    }
+
+    pub async fn create_directory(&self, path: &str) -> Result<(), ApiError> {
+        Ok(self.sftp.create_dir(path).await?)
+    }
+
+    pub async fn rename(&self, old_path: &str, new_path: &str) -> Result<(), ApiError> {
+        Ok(self.sftp.rename(old_path, new_path).await?)
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), ApiError> {
+        if self.sftp.symlink_metadata(path).await?.is_dir() {
+            self.sftp.remove_dir(path).await?;
+        } else {
+            self.sftp.remove_file(path).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn close(&self) -> Result<(), ApiError> {
+        Ok(self.sftp.close().await?)
+    }
+}
+
+#[derive(Clone)]
+pub struct SftpManager {
+    connections: HashMap<String, Arc<SftpConnection>>,
+}
+
+impl SftpManager {
+    pub fn new() -> Self {
+        Self {
+            connections: HashMap::new(),
+        }
+    }
+}
+
+impl SftpManager {
+    pub fn add(&mut self, id: String, connection: SftpConnection) {
+        self.connections.insert(id, Arc::new(connection));
+    }
+
+    pub fn get(&self, id: &str) -> Result<Arc<SftpConnection>, ApiError> {
+        self.connections
+            .get(id)
+            .cloned()
+            .ok_or(ApiError::SessionNotFound)
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Arc<SftpConnection>> {
+        self.connections.remove(id)
+    }
+
+    pub fn exist(&self, id: &str) -> bool {
+        self.connections.contains_key(id)
+    }
+}
+
+/// Copies `reader` into `writer`, reporting progress after every chunk.
+/// Returns `false` when the transfer was cancelled before it completed.
+pub async fn transfer<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    total: u64,
+    event_emitter: &EventEmitter,
+    cancel_token: &CancellationToken,
+) -> Result<bool, ApiError>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut buffer = vec![0u8; TRANSFER_CHUNK_SIZE];
+    let mut transferred: u64 = 0;
+
+    event_emitter.emit_progress(transferred, total).await?;
+
+    loop {
+        let length = tokio::select! {
+            length = reader.read(&mut buffer) => length?,
+            _ = cancel_token.cancelled() => return Ok(false),
+        };
+
+        if length == 0 {
+            break;
+        }
+
+        writer.write_all(&buffer[..length]).await?;
+        transferred += length as u64;
+
+        event_emitter.emit_progress(transferred, total).await?;
+    }
+
+    writer.shutdown().await?;
+
+    Ok(true)
+}
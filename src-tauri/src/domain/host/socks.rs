@@ -0,0 +1,185 @@
+use crate::infrastructure::error::ApiError;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const SOCKS4_VERSION: u8 = 0x04;
+const SOCKS5_VERSION: u8 = 0x05;
+
+const COMMAND_CONNECT: u8 = 0x01;
+
+const SOCKS5_NO_AUTHENTICATION: u8 = 0x00;
+const SOCKS5_NO_ACCEPTABLE_METHODS: u8 = 0xff;
+
+const SOCKS5_ADDRESS_IPV4: u8 = 0x01;
+const SOCKS5_ADDRESS_DOMAIN: u8 = 0x03;
+const SOCKS5_ADDRESS_IPV6: u8 = 0x04;
+
+const SOCKS5_REPLY_SUCCEEDED: u8 = 0x00;
+const SOCKS5_REPLY_GENERAL_FAILURE: u8 = 0x01;
+const SOCKS5_REPLY_COMMAND_NOT_SUPPORTED: u8 = 0x07;
+const SOCKS5_REPLY_ADDRESS_NOT_SUPPORTED: u8 = 0x08;
+
+const SOCKS4_REPLY_GRANTED: u8 = 0x5a;
+const SOCKS4_REPLY_REJECTED: u8 = 0x5b;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SocksVersion {
+    V4,
+    V5,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SocksRequest {
+    pub version: SocksVersion,
+    pub address: String,
+    pub port: u32,
+}
+
+impl SocksRequest {
+    /// Performs the client greeting and reads the CONNECT request, supporting
+    /// SOCKS4, SOCKS4a and unauthenticated SOCKS5.
+    pub async fn read(socket: &mut TcpStream) -> Result<Self, ApiError> {
+        match socket.read_u8().await? {
+            SOCKS4_VERSION => Self::read_socks4(socket).await,
+            SOCKS5_VERSION => Self::read_socks5(socket).await,
+            version => Err(ApiError::InvalidSocksRequest {
+                reason: format!("unsupported version {version}"),
+            }),
+        }
+    }
+
+    async fn read_socks4(socket: &mut TcpStream) -> Result<Self, ApiError> {
+        let command = socket.read_u8().await?;
+        let port = socket.read_u16().await?;
+        let mut ip = [0u8; 4];
+        socket.read_exact(&mut ip).await?;
+
+        // User ID, which is not used for anything.
+        read_null_terminated(socket).await?;
+
+        if command != COMMAND_CONNECT {
+            reply_socks4(socket, SOCKS4_REPLY_REJECTED).await?;
+            return Err(ApiError::InvalidSocksRequest {
+                reason: format!("unsupported command {command}"),
+            });
+        }
+
+        // SOCKS4a signals a trailing domain name with an address of 0.0.0.x
+        let address = if ip[..3] == [0, 0, 0] && ip[3] != 0 {
+            read_null_terminated(socket).await?
+        } else {
+            Ipv4Addr::from(ip).to_string()
+        };
+
+        Ok(Self {
+            version: SocksVersion::V4,
+            address,
+            port: port as u32,
+        })
+    }
+
+    async fn read_socks5(socket: &mut TcpStream) -> Result<Self, ApiError> {
+        let method_count = socket.read_u8().await?;
+        let mut methods = vec![0u8; method_count as usize];
+        socket.read_exact(&mut methods).await?;
+
+        if !methods.contains(&SOCKS5_NO_AUTHENTICATION) {
+            socket
+                .write_all(&[SOCKS5_VERSION, SOCKS5_NO_ACCEPTABLE_METHODS])
+                .await?;
+            return Err(ApiError::InvalidSocksRequest {
+                reason: "no acceptable authentication method".to_string(),
+            });
+        }
+        socket
+            .write_all(&[SOCKS5_VERSION, SOCKS5_NO_AUTHENTICATION])
+            .await?;
+
+        let mut header = [0u8; 4];
+        socket.read_exact(&mut header).await?;
+        let [_, command, _, address_type] = header;
+
+        let address = match address_type {
+            SOCKS5_ADDRESS_IPV4 => {
+                let mut ip = [0u8; 4];
+                socket.read_exact(&mut ip).await?;
+                Ipv4Addr::from(ip).to_string()
+            }
+            SOCKS5_ADDRESS_DOMAIN => {
+                let length = socket.read_u8().await?;
+                let mut domain = vec![0u8; length as usize];
+                socket.read_exact(&mut domain).await?;
+                String::from_utf8(domain)?
+            }
+            SOCKS5_ADDRESS_IPV6 => {
+                let mut ip = [0u8; 16];
+                socket.read_exact(&mut ip).await?;
+                Ipv6Addr::from(ip).to_string()
+            }
+            _ => {
+                reply_socks5(socket, SOCKS5_REPLY_ADDRESS_NOT_SUPPORTED).await?;
+                return Err(ApiError::InvalidSocksRequest {
+                    reason: format!("unsupported address type {address_type}"),
+                });
+            }
+        };
+        let port = socket.read_u16().await?;
+
+        if command != COMMAND_CONNECT {
+            reply_socks5(socket, SOCKS5_REPLY_COMMAND_NOT_SUPPORTED).await?;
+            return Err(ApiError::InvalidSocksRequest {
+                reason: format!("unsupported command {command}"),
+            });
+        }
+
+        Ok(Self {
+            version: SocksVersion::V5,
+            address,
+            port: port as u32,
+        })
+    }
+}
+
+impl SocksRequest {
+    pub async fn reply(&self, socket: &mut TcpStream, succeeded: bool) -> Result<(), ApiError> {
+        match (self.version, succeeded) {
+            (SocksVersion::V4, true) => reply_socks4(socket, SOCKS4_REPLY_GRANTED).await,
+            (SocksVersion::V4, false) => reply_socks4(socket, SOCKS4_REPLY_REJECTED).await,
+            (SocksVersion::V5, true) => reply_socks5(socket, SOCKS5_REPLY_SUCCEEDED).await,
+            (SocksVersion::V5, false) => reply_socks5(socket, SOCKS5_REPLY_GENERAL_FAILURE).await,
+        }
+    }
+}
+
+async fn read_null_terminated(socket: &mut TcpStream) -> Result<String, ApiError> {
+    let mut bytes = vec![];
+    loop {
+        match socket.read_u8().await? {
+            0 => break,
+            byte => bytes.push(byte),
+        }
+    }
+    Ok(String::from_utf8(bytes)?)
+}
+
+async fn reply_socks4(socket: &mut TcpStream, status: u8) -> Result<(), ApiError> {
+    Ok(socket.write_all(&[0, status, 0, 0, 0, 0, 0, 0]).await?)
+}
+
+async fn reply_socks5(socket: &mut TcpStream, status: u8) -> Result<(), ApiError> {
+    Ok(socket
+        .write_all(&[
+            SOCKS5_VERSION,
+            status,
+            0,
+            SOCKS5_ADDRESS_IPV4,
+            0,
+            0,
+            0,
+            0,
+            0,
+            0,
+        ])
+        .await?)
+}
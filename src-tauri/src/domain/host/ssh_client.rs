@@ -1,33 +1,109 @@
+use crate::domain::host::agent::connect_agent;
 use crate::domain::host::event::{Data, EventEmitter, StatusType};
+use crate::domain::host::models::{Host, RemoteForward};
+use crate::domain::known_host::models::{verify_known_host, KnownHost, KnownHostStatus};
+use crate::domain::store::r#enum::StoreKey;
+use crate::domain::store::store_manager::StoreManager;
 use crate::infrastructure::error::ApiError;
-use async_trait::async_trait;
-use russh::client::Handler;
+use russh::client::{Handler, Msg, Session};
 use russh::keys::HashAlg::Sha512;
 use russh::keys::PublicKey;
+use russh::Channel;
 use std::sync::Arc;
+use tokio::io;
+use tokio::net::TcpStream;
 
 #[derive(Clone)]
 pub struct SshClient {
     event_emitter: Arc<EventEmitter>,
-    pub fingerprint: Option<String>,
+    host_id: String,
+    address: String,
+    port: u32,
+    fingerprint: Option<String>,
+    store_manager: StoreManager,
     should_check_public_key: bool,
+    remote_forward: Option<RemoteForward>,
+    is_jump_host: bool,
+    forward_agent: bool,
 }
 
 impl SshClient {
     pub fn new(
         event_emitter: Arc<EventEmitter>,
-        fingerprint: Option<String>,
+        host: &Host,
+        store_manager: StoreManager,
         should_check_public_key: bool,
+        remote_forward: Option<RemoteForward>,
+        is_jump_host: bool,
     ) -> Self {
         Self {
             event_emitter,
-            fingerprint,
+            host_id: host.id.clone(),
+            address: host.address.clone(),
+            port: host.port,
+            fingerprint: host.fingerprint.clone(),
+            store_manager,
             should_check_public_key,
+            remote_forward,
+            is_jump_host,
+            forward_agent: host.forward_agent && !is_jump_host,
         }
     }
 }
 
-#[async_trait]
+impl SshClient {
+    fn verify_public_key(&mut self, public_key: &PublicKey) -> Result<KnownHostStatus, ApiError> {
+        let known_hosts = self
+            .store_manager
+            .get_data::<Vec<KnownHost>>(StoreKey::KnownHosts)?;
+
+        let status = verify_known_host(&known_hosts, &self.address, self.port, public_key);
+        if status != KnownHostStatus::Unknown {
+            return Ok(status);
+        }
+
+        // Hosts trusted before the known hosts store existed only pinned a
+        // fingerprint. A matching key is moved into the store and the pin
+        // dropped, any other key is a changed key and the pin is kept.
+        match &self.fingerprint {
+            Some(fingerprint) if fingerprint == &public_key.fingerprint(Sha512).to_string() => {
+                self.trust_public_key(public_key)?;
+                self.clear_legacy_fingerprint()?;
+                self.fingerprint = None;
+                Ok(KnownHostStatus::Trusted)
+            }
+            Some(_) => Ok(KnownHostStatus::Changed),
+            None => Ok(KnownHostStatus::Unknown),
+        }
+    }
+
+    fn trust_public_key(&self, public_key: &PublicKey) -> Result<(), ApiError> {
+        let mut known_hosts = self
+            .store_manager
+            .get_data::<Vec<KnownHost>>(StoreKey::KnownHosts)?;
+
+        known_hosts.push(KnownHost::from_public_key(
+            &self.address,
+            self.port,
+            public_key,
+        ));
+
+        self.store_manager
+            .update_data(StoreKey::KnownHosts, known_hosts)
+    }
+
+    fn clear_legacy_fingerprint(&self) -> Result<(), ApiError> {
+        let mut hosts = self.store_manager.get_data::<Vec<Host>>(StoreKey::Hosts)?;
+
+        if let Some(host) = hosts.iter_mut().find(|host| host.id == self.host_id) {
+            host.fingerprint = None;
+            self.store_manager.update_data(StoreKey::Hosts, hosts)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Handler for SshClient {
     type Error = ApiError;
 
@@ -35,23 +111,128 @@ impl Handler for SshClient {
         &mut self,
         server_public_key: &PublicKey,
     ) -> Result<bool, Self::Error> {
-        if !self.should_check_public_key {
-            return Ok(true);
-        }
-
         let fingerprint = server_public_key.fingerprint(Sha512).to_string();
 
-        if let Some(fp) = &self.fingerprint {
-            if fp == &fingerprint {
-                self.event_emitter.emit(Data::TrustPublicKey(true)).await?;
-                return Ok(true);
+        match self.verify_public_key(server_public_key)? {
+            KnownHostStatus::Trusted => {
+                if self.should_check_public_key && !self.is_jump_host {
+                    self.event_emitter.emit(Data::TrustPublicKey(true)).await?;
+                }
+                Ok(true)
+            }
+            KnownHostStatus::Revoked => {
+                log::warn!("Host key of {} has been revoked", self.host_id);
+                let status = if self.is_jump_host {
+                    StatusType::JumpHostKeyRevoked((self.host_id.clone(), fingerprint))
+                } else {
+                    StatusType::HostKeyRevoked(fingerprint)
+                };
+                self.event_emitter.emit_status(status).await?;
+                Ok(false)
             }
+            KnownHostStatus::Changed => {
+                log::warn!("Host key of {} has changed", self.host_id);
+                let status = if self.is_jump_host {
+                    StatusType::JumpHostKeyChanged((self.host_id.clone(), fingerprint))
+                } else {
+                    StatusType::HostKeyChanged(fingerprint)
+                };
+                self.event_emitter.emit_status(status).await?;
+                Ok(false)
+            }
+            KnownHostStatus::Unknown if !self.should_check_public_key => Ok(false),
+            KnownHostStatus::Unknown => {
+                let status = if self.is_jump_host {
+                    StatusType::NewJumpHostPublicKeyFound((
+                        self.host_id.clone(),
+                        fingerprint.clone(),
+                    ))
+                } else {
+                    StatusType::NewPublicKeyFound(fingerprint.clone())
+                };
+
+                if !self
+                    .event_emitter
+                    .wait_for_host_key_trust(&self.host_id, &fingerprint, status)
+                    .await?
+                {
+                    return Ok(false);
+                }
+
+                self.trust_public_key(server_public_key)?;
+                if !self.is_jump_host {
+                    self.event_emitter.emit(Data::TrustPublicKey(true)).await?;
+                }
+                Ok(true)
+            }
+        }
+    }
+
+    async fn server_channel_open_forwarded_tcpip(
+        &mut self,
+        channel: Channel<Msg>,
+        connected_address: &str,
+        connected_port: u32,
+        originator_address: &str,
+        originator_port: u32,
+        _session: &mut Session,
+    ) -> Result<(), Self::Error> {
+        let remote_forward = if let Some(remote_forward) = &self.remote_forward {
+            remote_forward.clone()
+        } else {
+            log::warn!("Rejected forwarded connection to {connected_address}:{connected_port}");
+            return Ok(());
+        };
+
+        log::debug!(
+            "Forwarding {originator_address}:{originator_port} from {connected_address}:{connected_port} to {}:{}",
+            remote_forward.local_address,
+            remote_forward.local_port
+        );
+
+        tokio::spawn(async move {
+            let mut socket = TcpStream::connect(format!(
+                "{}:{}",
+                remote_forward.local_address, remote_forward.local_port
+            ))
+            .await?;
+            let mut stream = channel.into_stream();
+
+            io::copy_bidirectional(&mut socket, &mut stream).await?;
+
+            Ok::<(), ApiError>(())
+        });
+
+        Ok(())
+    }
+
+    async fn server_channel_open_agent_forward(
+        &mut self,
+        channel: Channel<Msg>,
+        _session: &mut Session,
+    ) -> Result<(), Self::Error> {
+        if !self.forward_agent {
+            log::warn!("Rejected agent forwarding request from {}", self.host_id);
+            self.event_emitter
+                .emit_status(StatusType::AgentForwardRejected)
+                .await?;
+            return Ok(());
         }
 
+        log::info!("Forwarding local agent to {}", self.host_id);
         self.event_emitter
-            .emit_status(StatusType::NewPublicKeyFound(fingerprint.to_string()))
+            .emit_status(StatusType::AgentForwarded)
             .await?;
 
-        Ok(true)
+        tokio::spawn(async move {
+            let mut agent = connect_agent().await?.into_inner();
+            let mut stream = channel.into_stream();
+
+            io::copy_bidirectional(&mut agent, &mut stream).await?;
+
+            Ok::<(), ApiError>(())
+        });
+
+        Ok(())
     }
 }
@@ -1,8 +1,10 @@
 use crate::domain::identity::models::Identity;
+use crate::domain::identity::totp::Totp;
 use crate::domain::store::r#enum::StoreKey;
 use crate::infrastructure::app::AppData;
 use crate::infrastructure::error::ApiError;
 use crate::infrastructure::response::Response;
+use serde_json::json;
 use tauri;
 use tauri::State;
 use tokio::sync::Mutex;
@@ -25,14 +27,25 @@ pub async fn add_identity(
     username: String,
     password: String,
     private_key_ref: String,
+    use_agent: Option<bool>,
+    totp: Option<String>,
 ) -> Result<Response, ApiError> {
     log::debug!("add_identity called");
 
+    let totp = parse_totp(totp)?;
+
     let store_manager = &state.lock().await.store_manager;
 
     let mut identities = store_manager.get_data::<Vec<Identity>>(StoreKey::Identities)?;
 
-    let identity = Identity::new(Some(label), username, Some(password), Some(private_key_ref));
+    let identity = Identity::new(
+        Some(label),
+        username,
+        Some(password),
+        Some(private_key_ref),
+        use_agent.unwrap_or_default(),
+        totp.flatten(),
+    );
 
     identities.push(identity);
 
@@ -49,9 +62,13 @@ pub async fn update_identity(
     username: String,
     password: String,
     private_key_ref: String,
+    use_agent: Option<bool>,
+    totp: Option<String>,
 ) -> Result<Response, ApiError> {
     log::debug!("add_identity called");
 
+    let totp = parse_totp(totp)?;
+
     let store_manager = &state.lock().await.store_manager;
 
     let mut identities = store_manager.get_data::<Vec<Identity>>(StoreKey::Identities)?;
@@ -61,6 +78,12 @@ pub async fn update_identity(
         identity.username = username;
         identity.password = Some(password);
         identity.private_key_ref = Some(private_key_ref);
+        if let Some(use_agent) = use_agent {
+            identity.use_agent = use_agent;
+        }
+        if let Some(totp) = totp {
+            identity.totp = totp;
+        }
     } else {
         return Err(ApiError::NotFound {
             item: "identity".to_string(),
@@ -95,3 +118,40 @@ pub async fn delete_identity(
 
     Ok(Response::new_ok_message())
 }
+
+#[tauri::command]
+pub async fn get_totp_code(
+    state: State<'_, Mutex<AppData>>,
+    id: String,
+) -> Result<Response, ApiError> {
+    log::debug!("get_totp_code called");
+
+    let store_manager = &state.lock().await.store_manager;
+
+    let identity =
+        if let Some(identity) = store_manager.get_item::<Identity>(StoreKey::Identities, &id)? {
+            identity
+        } else {
+            return Err(ApiError::NotFound {
+                item: "identity".to_string(),
+            });
+        };
+
+    if let Some(totp) = identity.totp {
+        Ok(Response::from_value(json!(totp.now()?)))
+    } else {
+        Err(ApiError::NotFound {
+            item: "TOTP secret".to_string(),
+        })
+    }
+}
+
+/// Parses the TOTP input of the identity form. An empty string clears the
+/// secret, while `None` leaves it untouched.
+fn parse_totp(totp: Option<String>) -> Result<Option<Option<Totp>>, ApiError> {
+    match totp {
+        Some(totp) if totp.trim().is_empty() => Ok(Some(None)),
+        Some(totp) => Ok(Some(Some(Totp::parse(&totp)?))),
+        None => Ok(None),
+    }
+}
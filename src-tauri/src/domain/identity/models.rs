@@ -1,6 +1,7 @@
 use nanoid::nanoid;
 use serde::{Deserialize, Serialize};
 
+use crate::domain::identity::totp::Totp;
 use crate::domain::traits::Identifiable;
 use crate::infrastructure::transform::empty_to_null;
 
@@ -15,6 +16,10 @@ pub struct Identity {
     pub password: Option<String>,
     #[serde(serialize_with = "empty_to_null")]
     pub private_key_ref: Option<String>,
+    #[serde(default)]
+    pub use_agent: bool,
+    #[serde(default)]
+    pub totp: Option<Totp>,
 }
 
 impl Identity {
@@ -23,6 +28,8 @@ impl Identity {
         username: String,
         password: Option<String>,
         private_key_ref: Option<String>,
+        use_agent: bool,
+        totp: Option<Totp>,
     ) -> Self {
         Self {
             id: nanoid!(),
@@ -30,6 +37,8 @@ impl Identity {
             username,
             password,
             private_key_ref,
+            use_agent,
+            totp,
         }
     }
 }
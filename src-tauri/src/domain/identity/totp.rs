@@ -0,0 +1,306 @@
+use data_encoding::BASE32_NOPAD;
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
+
+use crate::infrastructure::error::ApiError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TotpAlgorithm {
+    #[default]
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+/// RFC 6238 parameters of a TOTP generator. `Debug` is implemented by hand
+/// so the secret never ends up in logs.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Totp {
+    pub secret: String,
+    #[serde(default)]
+    pub algorithm: TotpAlgorithm,
+    #[serde(default = "default_digits")]
+    pub digits: u32,
+    #[serde(default = "default_period")]
+    pub period: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpCode {
+    pub code: String,
+    pub remaining_seconds: u64,
+}
+
+fn default_digits() -> u32 {
+    6
+}
+
+fn default_period() -> u64 {
+    30
+}
+
+impl std::fmt::Debug for Totp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Totp")
+            .field("secret", &"<redacted>")
+            .field("algorithm", &self.algorithm)
+            .field("digits", &self.digits)
+            .field("period", &self.period)
+            .finish()
+    }
+}
+
+impl Totp {
+    /// Parses either an `otpauth://totp/...` URI or a bare base32 secret.
+    pub fn parse(input: &str) -> Result<Self, ApiError> {
+        let input = input.trim();
+
+        let totp = if input.starts_with("otpauth://") {
+            Self::from_uri(input)?
+        } else {
+            Self {
+                secret: normalize_secret(input),
+                algorithm: TotpAlgorithm::default(),
+                digits: default_digits(),
+                period: default_period(),
+            }
+        };
+
+        totp.key()?;
+        Ok(totp)
+    }
+
+    fn from_uri(uri: &str) -> Result<Self, ApiError> {
+        let url = Url::parse(uri).map_err(|_| invalid("malformed otpauth URI"))?;
+        if url.host_str() != Some("totp") {
+            return Err(invalid("only totp URIs are supported"));
+        }
+
+        let mut totp = Self {
+            secret: String::new(),
+            algorithm: TotpAlgorithm::default(),
+            digits: default_digits(),
+            period: default_period(),
+        };
+
+        for (key, value) in url.query_pairs() {
+            match key.as_ref() {
+                "secret" => totp.secret = normalize_secret(&value),
+                "algorithm" => {
+                    totp.algorithm = match value.to_uppercase().as_str() {
+                        "SHA1" => TotpAlgorithm::Sha1,
+                        "SHA256" => TotpAlgorithm::Sha256,
+                        "SHA512" => TotpAlgorithm::Sha512,
+                        _ => return Err(invalid("unsupported algorithm")),
+                    }
+                }
+                "digits" => {
+                    totp.digits = value
+                        .parse()
+                        .ok()
+                        .filter(|digits| (6..=8).contains(digits))
+                        .ok_or(invalid("digits must be between 6 and 8"))?
+                }
+                "period" => {
+                    totp.period = value
+                        .parse()
+                        .ok()
+                        .filter(|period| *period > 0)
+                        .ok_or(invalid("period must be a positive number"))?
+                }
+                _ => {}
+            }
+        }
+
+        Ok(totp)
+    }
+
+    fn key(&self) -> Result<Vec<u8>, ApiError> {
+        let key = BASE32_NOPAD
+            .decode(self.secret.as_bytes())
+            .map_err(|_| invalid("secret is not valid base32"))?;
+        if key.is_empty() {
+            return Err(invalid("secret is empty"));
+        }
+        Ok(key)
+    }
+
+    pub fn now(&self) -> Result<TotpCode, ApiError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+
+        Ok(TotpCode {
+            code: self.generate(timestamp / self.period)?,
+            remaining_seconds: self.period - timestamp % self.period,
+        })
+    }
+
+    fn generate(&self, counter: u64) -> Result<String, ApiError> {
+        let key = self.key()?;
+        let message = counter.to_be_bytes();
+
+        let digest = match self.algorithm {
+            TotpAlgorithm::Sha1 => hmac_digest::<Hmac<Sha1>>(&key, &message)?,
+            TotpAlgorithm::Sha256 => hmac_digest::<Hmac<Sha256>>(&key, &message)?,
+            TotpAlgorithm::Sha512 => hmac_digest::<Hmac<Sha512>>(&key, &message)?,
+        };
+
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([
+            digest[offset] & 0x7f,
+            digest[offset + 1],
+            digest[offset + 2],
+            digest[offset + 3],
+        ]);
+
+        Ok(format!(
+            "{:0width$}",
+            binary % 10u32.pow(self.digits),
+            width = self.digits as usize
+        ))
+    }
+}
+
+fn hmac_digest<M: Mac + KeyInit>(key: &[u8], message: &[u8]) -> Result<Vec<u8>, ApiError> {
+    let mut mac = <M as Mac>::new_from_slice(key).map_err(|_| invalid("invalid key length"))?;
+    mac.update(message);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn normalize_secret(secret: &str) -> String {
+    secret
+        .chars()
+        .filter(|character| !character.is_whitespace() && *character != '-' && *character != '=')
+        .collect::<String>()
+        .to_uppercase()
+}
+
+fn invalid(reason: &str) -> ApiError {
+    ApiError::InvalidTotpSecret {
+        reason: reason.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RFC_6238_TIMES: [u64; 6] = [
+        59,
+        1111111109,
+        1111111111,
+        1234567890,
+        2000000000,
+        20000000000,
+    ];
+
+    fn rfc_6238_totp(algorithm: TotpAlgorithm, seed: &[u8]) -> Totp {
+        Totp {
+            secret: BASE32_NOPAD.encode(seed),
+            algorithm,
+            digits: 8,
+            period: 30,
+        }
+    }
+
+    fn assert_rfc_6238_codes(totp: &Totp, codes: [&str; 6]) {
+        for (time, code) in RFC_6238_TIMES.iter().zip(codes) {
+            assert_eq!(
+                totp.generate(time / totp.period).unwrap(),
+                code,
+                "T = {time}"
+            );
+        }
+    }
+
+    #[test]
+    fn generates_rfc_6238_sha1_vectors() {
+        let totp = rfc_6238_totp(TotpAlgorithm::Sha1, b"12345678901234567890");
+        assert_rfc_6238_codes(
+            &totp,
+            [
+                "94287082", "07081804", "14050471", "89005924", "69279037", "65353130",
+            ],
+        );
+    }
+
+    #[test]
+    fn generates_rfc_6238_sha256_vectors() {
+        let totp = rfc_6238_totp(TotpAlgorithm::Sha256, b"12345678901234567890123456789012");
+        assert_rfc_6238_codes(
+            &totp,
+            [
+                "46119246", "68084774", "67062674", "91819424", "90698825", "77737706",
+            ],
+        );
+    }
+
+    #[test]
+    fn generates_rfc_6238_sha512_vectors() {
+        let totp = rfc_6238_totp(
+            TotpAlgorithm::Sha512,
+            b"1234567890123456789012345678901234567890123456789012345678901234",
+        );
+        assert_rfc_6238_codes(
+            &totp,
+            [
+                "90693936", "25091201", "99943326", "93441116", "38618901", "47863826",
+            ],
+        );
+    }
+
+    #[test]
+    fn parses_otpauth_uri() {
+        let totp = Totp::parse(
+            "otpauth://totp/Example:alice@example.com?secret=JBSWY3DPEHPK3PXP&issuer=Example&algorithm=SHA256&digits=8&period=60",
+        )
+        .unwrap();
+
+        assert_eq!(totp.secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(totp.algorithm, TotpAlgorithm::Sha256);
+        assert_eq!(totp.digits, 8);
+        assert_eq!(totp.period, 60);
+    }
+
+    #[test]
+    fn parses_otpauth_uri_with_defaults() {
+        let totp = Totp::parse("otpauth://totp/alice?secret=jbsw%20y3dp-ehpk3pxp").unwrap();
+
+        assert_eq!(totp.secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(totp.algorithm, TotpAlgorithm::Sha1);
+        assert_eq!(totp.digits, 6);
+        assert_eq!(totp.period, 30);
+    }
+
+    #[test]
+    fn parses_bare_secret() {
+        let totp = Totp::parse("  jbsw y3dp ehpk 3pxp  ").unwrap();
+
+        assert_eq!(totp.secret, "JBSWY3DPEHPK3PXP");
+        assert_eq!(totp.digits, 6);
+    }
+
+    #[test]
+    fn rejects_invalid_uris() {
+        for uri in [
+            "otpauth://hotp/alice?secret=JBSWY3DPEHPK3PXP&counter=0",
+            "otpauth://totp/alice?secret=JBSWY3DPEHPK3PXP&algorithm=MD5",
+            "otpauth://totp/alice?secret=JBSWY3DPEHPK3PXP&digits=10",
+            "otpauth://totp/alice?secret=JBSWY3DPEHPK3PXP&period=0",
+            "otpauth://totp/alice?issuer=Example",
+            "otpauth://totp/alice?secret=not-base32!",
+        ] {
+            assert!(Totp::parse(uri).is_err(), "{uri}");
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::domain::known_host::models::KnownHost;
+use crate::domain::store::r#enum::StoreKey;
+use crate::infrastructure::app::AppData;
+use crate::infrastructure::error::ApiError;
+use crate::infrastructure::response::Response;
+use std::path::PathBuf;
+use tauri;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+
+#[tauri::command]
+pub async fn list_known_hosts(state: State<'_, Mutex<AppData>>) -> Result<Response, ApiError> {
+    log::debug!("list_known_hosts called");
+
+    let store_manager = &state.lock().await.store_manager;
+
+    let known_hosts = store_manager.get_data::<Vec<KnownHost>>(StoreKey::KnownHosts)?;
+
+    Ok(Response::from_data(known_hosts))
+}
+
+#[tauri::command]
+pub async fn delete_known_host(
+    state: State<'_, Mutex<AppData>>,
+    id: String,
+) -> Result<Response, ApiError> {
+    log::debug!("delete_known_host called");
+
+    let store_manager = &state.lock().await.store_manager;
+
+    let mut known_hosts = store_manager.get_data::<Vec<KnownHost>>(StoreKey::KnownHosts)?;
+
+    if let Some(position) = known_hosts
+        .iter()
+        .position(|known_host| known_host.id == id)
+    {
+        known_hosts.remove(position)
+    } else {
+        return Err(ApiError::NotFound {
+            item: "known_host".to_string(),
+        });
+    };
+
+    store_manager.update_data(StoreKey::KnownHosts, known_hosts)?;
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+pub async fn import_known_hosts(
+    app: AppHandle,
+    state: State<'_, Mutex<AppData>>,
+    path: Option<String>,
+) -> Result<Response, ApiError> {
+    log::debug!("import_known_hosts called");
+
+    let path = known_hosts_path(&app, path)?;
+    let content = tokio::fs::read_to_string(&path).await?;
+
+    let store_manager = &state.lock().await.store_manager;
+
+    let mut known_hosts = store_manager.get_data::<Vec<KnownHost>>(StoreKey::KnownHosts)?;
+
+    let mut imported = vec![];
+    for known_host in content.lines().filter_map(KnownHost::parse_line) {
+        if known_hosts
+            .iter()
+            .any(|existing| existing.is_same_entry(&known_host))
+        {
+            continue;
+        }
+        known_hosts.push(known_host.clone());
+        imported.push(known_host);
+    }
+
+    store_manager.update_data(StoreKey::KnownHosts, known_hosts)?;
+
+    Ok(Response::from_data(imported))
+}
+
+#[tauri::command]
+pub async fn export_known_hosts(
+    app: AppHandle,
+    state: State<'_, Mutex<AppData>>,
+    path: Option<String>,
+) -> Result<Response, ApiError> {
+    log::debug!("export_known_hosts called");
+
+    let path = known_hosts_path(&app, path)?;
+
+    let known_hosts = {
+        let store_manager = &state.lock().await.store_manager;
+        store_manager.get_data::<Vec<KnownHost>>(StoreKey::KnownHosts)?
+    };
+
+    let mut content = known_hosts
+        .iter()
+        .map(KnownHost::to_line)
+        .collect::<Vec<String>>()
+        .join("\n");
+    content.push('\n');
+
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&path, content).await?;
+
+    Ok(Response::new_ok_message())
+}
+
+fn known_hosts_path(app: &AppHandle, path: Option<String>) -> Result<PathBuf, ApiError> {
+    match path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => Ok(app.path().home_dir()?.join(".ssh").join("known_hosts")),
+    }
+}
@@ -0,0 +1,256 @@
+use data_encoding::BASE64;
+use hmac::{Hmac, Mac};
+use nanoid::nanoid;
+use russh::keys::{parse_public_key_base64, PublicKey, PublicKeyBase64};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+
+use crate::domain::traits::Identifiable;
+use crate::infrastructure::transform::empty_to_null;
+
+const HASHED_HOST_PREFIX: &str = "|1|";
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub enum KnownHostMarker {
+    CertAuthority,
+    Revoked,
+}
+
+impl KnownHostMarker {
+    pub fn as_str(&self) -> &str {
+        match self {
+            KnownHostMarker::CertAuthority => "@cert-authority",
+            KnownHostMarker::Revoked => "@revoked",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownHost {
+    pub id: String,
+    pub marker: Option<KnownHostMarker>,
+    pub hosts: Vec<String>,
+    pub key_type: String,
+    pub key: String,
+    #[serde(serialize_with = "empty_to_null")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum KnownHostStatus {
+    Trusted,
+    Changed,
+    Revoked,
+    Unknown,
+}
+
+impl KnownHost {
+    pub fn new(
+        marker: Option<KnownHostMarker>,
+        hosts: Vec<String>,
+        key_type: String,
+        key: String,
+        comment: Option<String>,
+    ) -> Self {
+        Self {
+            id: nanoid!(),
+            marker,
+            hosts,
+            key_type,
+            key,
+            comment,
+        }
+    }
+
+    pub fn from_public_key(address: &str, port: u32, public_key: &PublicKey) -> Self {
+        Self::new(
+            None,
+            vec![host_port(address, port)],
+            public_key.algorithm().as_str().to_string(),
+            public_key.public_key_base64(),
+            None,
+        )
+    }
+
+    /// Parses a single line of an OpenSSH `known_hosts` file, returning `None`
+    /// for blank lines, comments and lines that cannot be understood.
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let mut fields = line.split_whitespace();
+        let mut field = fields.next()?;
+
+        let marker = match field {
+            "@cert-authority" => Some(KnownHostMarker::CertAuthority),
+            "@revoked" => Some(KnownHostMarker::Revoked),
+            _ => None,
+        };
+        if marker.is_some() {
+            field = fields.next()?;
+        }
+
+        let hosts = field.split(',').map(str::to_string).collect();
+        let key_type = fields.next()?.to_string();
+        let key = fields.next()?.to_string();
+        let comment = fields.collect::<Vec<&str>>().join(" ");
+
+        Some(Self::new(
+            marker,
+            hosts,
+            key_type,
+            key,
+            Some(comment).filter(|comment| !comment.is_empty()),
+        ))
+    }
+
+    pub fn to_line(&self) -> String {
+        let mut fields = vec![];
+        if let Some(marker) = &self.marker {
+            fields.push(marker.as_str().to_string());
+        }
+        fields.push(self.hosts.join(","));
+        fields.push(self.key_type.clone());
+        fields.push(self.key.clone());
+        if let Some(comment) = &self.comment {
+            fields.push(comment.clone());
+        }
+        fields.join(" ")
+    }
+
+    pub fn is_same_entry(&self, other: &KnownHost) -> bool {
+        self.marker == other.marker
+            && self.hosts == other.hosts
+            && self.key_type == other.key_type
+            && self.key == other.key
+    }
+
+    pub fn public_key(&self) -> Option<PublicKey> {
+        parse_public_key_base64(&self.key).ok()
+    }
+
+    pub fn matches_key(&self, public_key: &PublicKey) -> bool {
+        self.public_key().as_ref() == Some(public_key)
+    }
+
+    /// Evaluates the host patterns against `address:port`, honouring `*`/`?`
+    /// wildcards, `!` negations and hashed hostnames.
+    pub fn matches_host(&self, address: &str, port: u32) -> bool {
+        let name = host_port(address, port);
+
+        let mut matched = false;
+        for pattern in self.hosts.iter() {
+            let (negated, pattern) = match pattern.strip_prefix('!') {
+                Some(pattern) => (true, pattern),
+                None => (false, pattern.as_str()),
+            };
+
+            if match_pattern(&name, pattern) {
+                if negated {
+                    return false;
+                }
+                matched = true;
+            }
+        }
+        matched
+    }
+}
+
+impl Identifiable for KnownHost {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// Looks up `public_key` for `address:port` following OpenSSH semantics: a
+/// revoked key is always rejected, and a recorded key of the same type that
+/// differs from the presented one means the host key has changed.
+pub fn verify_known_host(
+    known_hosts: &[KnownHost],
+    address: &str,
+    port: u32,
+    public_key: &PublicKey,
+) -> KnownHostStatus {
+    if known_hosts.iter().any(|known_host| {
+        known_host.marker == Some(KnownHostMarker::Revoked) && known_host.matches_key(public_key)
+    }) {
+        return KnownHostStatus::Revoked;
+    }
+
+    let mut status = KnownHostStatus::Unknown;
+    for known_host in known_hosts
+        .iter()
+        .filter(|known_host| known_host.marker.is_none())
+        .filter(|known_host| known_host.matches_host(address, port))
+    {
+        if known_host.matches_key(public_key) {
+            return KnownHostStatus::Trusted;
+        }
+        if known_host.key_type == public_key.algorithm().as_str() {
+            status = KnownHostStatus::Changed;
+        }
+    }
+    status
+}
+
+pub fn host_port(address: &str, port: u32) -> String {
+    if port == 22 {
+        address.to_string()
+    } else {
+        format!("[{}]:{}", address, port)
+    }
+}
+
+fn match_pattern(name: &str, pattern: &str) -> bool {
+    if let Some(hashed) = pattern.strip_prefix(HASHED_HOST_PREFIX) {
+        return match_hashed(name, hashed);
+    }
+    match_wildcard(name, pattern)
+}
+
+fn match_hashed(name: &str, hashed: &str) -> bool {
+    let (salt, hash) = match hashed.split_once('|') {
+        Some(parts) => parts,
+        None => return false,
+    };
+    let (salt, hash) = match (
+        BASE64.decode(salt.as_bytes()),
+        BASE64.decode(hash.as_bytes()),
+    ) {
+        (Ok(salt), Ok(hash)) => (salt, hash),
+        _ => return false,
+    };
+
+    let mut hmac = match Hmac::<Sha1>::new_from_slice(&salt) {
+        Ok(hmac) => hmac,
+        Err(_) => return false,
+    };
+    hmac.update(name.as_bytes());
+    hmac.verify_slice(&hash).is_ok()
+}
+
+/// Case-insensitive glob matching supporting the `*` and `?` wildcards used by
+/// OpenSSH host patterns.
+pub fn match_wildcard(name: &str, pattern: &str) -> bool {
+    match_wildcard_bytes(
+        name.to_lowercase().as_bytes(),
+        pattern.to_lowercase().as_bytes(),
+    )
+}
+
+fn match_wildcard_bytes(name: &[u8], pattern: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            match_wildcard_bytes(name, &pattern[1..])
+                || (!name.is_empty() && match_wildcard_bytes(&name[1..], pattern))
+        }
+        (Some(b'?'), Some(_)) => match_wildcard_bytes(&name[1..], &pattern[1..]),
+        (Some(p), Some(n)) if p == n => match_wildcard_bytes(&name[1..], &pattern[1..]),
+        _ => false,
+    }
+}
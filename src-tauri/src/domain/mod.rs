@@ -1,8 +1,13 @@
+pub mod broadcast;
 pub mod future;
 pub mod gpt;
 pub mod host;
 pub mod identity;
+pub mod known_host;
 pub mod private_key;
+pub mod recording;
+pub mod session_log;
 pub mod setting;
+pub mod ssh_config;
 pub mod store;
 pub mod traits;
@@ -24,6 +24,8 @@ pub async fn add_private_key(
     state: State<'_, Mutex<AppData>>,
     label: String,
     content: String,
+    passphrase: Option<String>,
+    certificate: Option<String>,
 ) -> Result<Response, ApiError> {
     log::debug!("add_private_key called");
 
@@ -31,7 +33,8 @@ pub async fn add_private_key(
 
     let mut private_keys = store_manager.get_data::<Vec<PrivateKey>>(StoreKey::PrivateKeys)?;
 
-    let private_key = PrivateKey::new(label, content);
+    let private_key = PrivateKey::new(label, content, passphrase, certificate);
+    private_key.parse_certificate()?;
 
     private_keys.push(private_key.clone());
 
@@ -41,11 +44,16 @@ pub async fn add_private_key(
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_private_key(
     state: State<'_, Mutex<AppData>>,
     id: String,
     label: String,
     content: String,
+    passphrase: Option<String>,
+    clear_passphrase: Option<bool>,
+    certificate: Option<String>,
+    clear_certificate: Option<bool>,
 ) -> Result<Response, ApiError> {
     log::debug!("update_private_key called");
 
@@ -59,6 +67,19 @@ pub async fn update_private_key(
     {
         private_key.label = label;
         private_key.content = content;
+        // Omitted passphrases and certificates keep the stored ones, they are
+        // only removed on request.
+        if let Some(passphrase) = passphrase {
+            private_key.passphrase = Some(passphrase);
+        } else if clear_passphrase.unwrap_or(false) {
+            private_key.passphrase = None;
+        }
+        if let Some(certificate) = certificate {
+            private_key.certificate = Some(certificate);
+            private_key.parse_certificate()?;
+        } else if clear_certificate.unwrap_or(false) {
+            private_key.certificate = None;
+        }
         Some(private_key.clone())
     } else {
         None
@@ -100,3 +121,25 @@ pub async fn delete_private_key(
 
     Ok(Response::new_ok_message())
 }
+
+#[tauri::command]
+pub async fn get_certificate_info(
+    state: State<'_, Mutex<AppData>>,
+    id: String,
+) -> Result<Response, ApiError> {
+    log::debug!("get_certificate_info called");
+
+    let store_manager = &state.lock().await.store_manager;
+
+    let private_key = if let Some(private_key) =
+        store_manager.get_item::<PrivateKey>(StoreKey::PrivateKeys, &id)?
+    {
+        private_key
+    } else {
+        return Err(ApiError::NotFound {
+            item: format!("private_key {}", id),
+        });
+    };
+
+    Ok(Response::from_value(json!(private_key.certificate_info()?)))
+}
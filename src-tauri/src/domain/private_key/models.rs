@@ -1,7 +1,13 @@
 use nanoid::nanoid;
 use serde::{Deserialize, Serialize};
 
+use russh::keys::ssh_key::public::KeyData;
+use russh::keys::{decode_secret_key, Certificate, PrivateKey as SecretKey};
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use crate::domain::traits::Identifiable;
+use crate::infrastructure::error::ApiError;
+use crate::infrastructure::transform::empty_to_null;
 
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -9,16 +15,88 @@ pub struct PrivateKey {
     pub id: String,
     pub label: String,
     pub content: String,
+    #[serde(default, serialize_with = "empty_to_null")]
+    pub passphrase: Option<String>,
+    #[serde(default, serialize_with = "empty_to_null")]
+    pub certificate: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CertificateInfo {
+    pub key_id: String,
+    pub principals: Vec<String>,
+    pub valid_after: u64,
+    pub valid_before: u64,
+    pub expired: bool,
 }
 
 impl PrivateKey {
-    pub fn new(label: String, content: String) -> Self {
+    pub fn new(
+        label: String,
+        content: String,
+        passphrase: Option<String>,
+        certificate: Option<String>,
+    ) -> Self {
         Self {
             id: nanoid!(),
             label,
             content,
+            passphrase,
+            certificate,
+        }
+    }
+
+    /// Parses the attached OpenSSH user certificate, if any, and checks that
+    /// it certifies this key.
+    pub fn parse_certificate(&self) -> Result<Option<Certificate>, ApiError> {
+        let certificate = match self.certificate.as_deref().map(str::trim) {
+            Some(certificate) if !certificate.is_empty() => {
+                Certificate::from_openssh(certificate).map_err(russh::keys::Error::from)?
+            }
+            _ => return Ok(None),
+        };
+
+        match self.public_key_data() {
+            Some(key_data) if &key_data != certificate.public_key() => {
+                Err(ApiError::CertificateKeyMismatch {
+                    label: self.label.clone(),
+                })
+            }
+            _ => Ok(Some(certificate)),
         }
     }
+
+    /// The public half of the key. OpenSSH keys carry it unencrypted, other
+    /// formats only reveal it once decrypted with the stored passphrase.
+    fn public_key_data(&self) -> Option<KeyData> {
+        SecretKey::from_openssh(self.content.trim())
+            .ok()
+            .or_else(|| decode_secret_key(&self.content, self.passphrase.as_deref()).ok())
+            .map(|key| key.public_key().key_data().clone())
+    }
+
+    pub fn certificate_info(&self) -> Result<Option<CertificateInfo>, ApiError> {
+        Ok(self
+            .parse_certificate()?
+            .map(|certificate| CertificateInfo {
+                key_id: certificate.key_id().to_string(),
+                principals: certificate.valid_principals().to_vec(),
+                valid_after: certificate.valid_after(),
+                valid_before: certificate.valid_before(),
+                expired: !is_certificate_valid(&certificate),
+            }))
+    }
+}
+
+/// Whether the current time falls within the validity window of `certificate`.
+pub fn is_certificate_valid(certificate: &Certificate) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    certificate.valid_after() <= now && now < certificate.valid_before()
 }
 
 impl Identifiable for PrivateKey {
@@ -0,0 +1,173 @@
+use crate::domain::host::event::{Data, EventEmitter, SessionExit, StatusType};
+use crate::domain::recording::models::{Recording, RecordingEvent, RecordingHeader};
+use crate::domain::recording::recorder::{recording_id, recording_path, recordings_directory};
+use crate::infrastructure::app::AppData;
+use crate::infrastructure::error::ApiError;
+use crate::infrastructure::response::Response;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State, Window};
+use tokio::sync::Mutex;
+use tokio::time::{sleep_until, Instant};
+use tokio_util::bytes::Bytes;
+use tokio_util::sync::CancellationToken;
+
+#[tauri::command]
+pub async fn list_recordings(app: AppHandle) -> Result<Response, ApiError> {
+    log::debug!("list_recordings called");
+
+    let directory = recordings_directory(&app)?;
+    let mut recordings = vec![];
+
+    if tokio::fs::try_exists(&directory).await? {
+        let mut entries = tokio::fs::read_dir(&directory).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let id = if let Some(id) = recording_id(&entry.path()) {
+                id
+            } else {
+                continue;
+            };
+
+            let content = tokio::fs::read_to_string(entry.path()).await?;
+            if let Some(recording) = Recording::from_cast(&id, &content) {
+                recordings.push(recording);
+            }
+        }
+    }
+
+    recordings.sort_by_key(|recording| std::cmp::Reverse(recording.started_at));
+
+    Ok(Response::from_data(recordings))
+}
+
+#[tauri::command]
+pub async fn delete_recording(app: AppHandle, id: String) -> Result<Response, ApiError> {
+    log::debug!("delete_recording called");
+
+    let path = recording_path(&app, &id)?;
+    if !tokio::fs::try_exists(&path).await? {
+        return Err(ApiError::NotFound {
+            item: format!("recording {}", id),
+        });
+    }
+
+    tokio::fs::remove_file(path).await?;
+
+    Ok(Response::new_ok_message())
+}
+
+#[tauri::command]
+pub async fn export_recording(
+    app: AppHandle,
+    id: String,
+    path: String,
+) -> Result<Response, ApiError> {
+    log::debug!("export_recording called");
+
+    let source = recording_path(&app, &id)?;
+    if !tokio::fs::try_exists(&source).await? {
+        return Err(ApiError::NotFound {
+            item: format!("recording {}", id),
+        });
+    }
+
+    let destination = PathBuf::from(path);
+    if let Some(parent) = destination.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::copy(source, destination).await?;
+
+    Ok(Response::new_ok_message())
+}
+
+/// Replays a recording on `event_id` with the output and status events of a
+/// live terminal stream, so the terminal view can render it as is.
+#[tauri::command]
+pub async fn play_recording(
+    window: Window,
+    state: State<'_, Mutex<AppData>>,
+    id: String,
+    event_id: String,
+    speed: Option<f64>,
+) -> Result<Response, ApiError> {
+    log::debug!("play_recording called");
+
+    let path = recording_path(window.app_handle(), &id)?;
+    if !tokio::fs::try_exists(&path).await? {
+        return Err(ApiError::NotFound {
+            item: format!("recording {}", id),
+        });
+    }
+
+    let content = tokio::fs::read_to_string(path).await?;
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let header = if let Some(line) = lines.next() {
+        serde_json::from_str::<RecordingHeader>(line)?
+    } else {
+        return Err(ApiError::NotFound {
+            item: format!("recording {}", id),
+        });
+    };
+    let events = lines
+        .filter_map(|line| serde_json::from_str::<RecordingEvent>(line).ok())
+        .collect::<Vec<RecordingEvent>>();
+
+    let speed = speed.filter(|speed| *speed > 0.0).unwrap_or(1.0);
+
+    let cancel_token = CancellationToken::new();
+    let cloned_cancel_token = cancel_token.clone();
+
+    if !state
+        .lock()
+        .await
+        .future_manager
+        .try_add(cancel_token, &event_id)
+    {
+        return Ok(Response::new_ok_message());
+    }
+
+    let event_emitter = EventEmitter::new(window.clone(), event_id.clone());
+
+    let cloned_event_id = event_id.clone();
+    tokio::spawn(async move {
+        event_emitter
+            .emit(Data::Size((header.width, header.height)))
+            .await?;
+        event_emitter
+            .emit_status(StatusType::StartStreaming)
+            .await?;
+
+        let started_at = Instant::now();
+        for event in events {
+            let deadline = started_at + Duration::from_secs_f64(event.0.max(0.0) / speed);
+            tokio::select! {
+                _ = sleep_until(deadline) => {},
+                _ = cloned_cancel_token.cancelled() => return Ok(()),
+            }
+
+            match event.1.as_str() {
+                "o" => event_emitter.emit_out(Bytes::from(event.2)).await?,
+                "r" => {
+                    if let Some(size) = event.size() {
+                        event_emitter.emit(Data::Size(size)).await?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        event_emitter
+            .emit_status(StatusType::SessionEnded(SessionExit::default()))
+            .await?;
+        window
+            .state::<Mutex<AppData>>()
+            .lock()
+            .await
+            .future_manager
+            .abort(&cloned_event_id);
+
+        Ok::<(), ApiError>(())
+    });
+
+    Ok(Response::new_ok_message())
+}
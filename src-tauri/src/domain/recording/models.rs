@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const RECORDING_VERSION: u32 = 2;
+
+/// First line of an asciinema v2 cast file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordingHeader {
+    pub version: u32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub timestamp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+}
+
+/// An `[time, code, data]` line of a cast file, `code` being `o` for output,
+/// `i` for input and `r` for a resize to `COLSxROWS`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordingEvent(pub f64, pub String, pub String);
+
+impl RecordingEvent {
+    pub fn size(&self) -> Option<(u32, u32)> {
+        let (cols, rows) = self.2.split_once('x')?;
+        Some((cols.parse().ok()?, rows.parse().ok()?))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Recording {
+    pub id: String,
+    pub title: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub started_at: Option<u64>,
+    pub duration: f64,
+    pub size: u64,
+}
+
+impl Recording {
+    pub fn from_cast(id: &str, content: &str) -> Option<Self> {
+        let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+        let header = serde_json::from_str::<RecordingHeader>(lines.next()?).ok()?;
+        if header.version != RECORDING_VERSION {
+            return None;
+        }
+
+        let duration = lines
+            .next_back()
+            .and_then(|line| serde_json::from_str::<RecordingEvent>(line).ok())
+            .map(|event| event.0)
+            .unwrap_or_default();
+
+        Some(Self {
+            id: id.to_string(),
+            title: header.title,
+            width: header.width,
+            height: header.height,
+            started_at: header.timestamp,
+            duration,
+            size: content.len() as u64,
+        })
+    }
+}
@@ -0,0 +1,115 @@
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+use crate::domain::recording::models::RecordingHeader;
+use crate::infrastructure::error::ApiError;
+
+const RECORDING_EXTENSION: &str = "cast";
+
+/// Tees a terminal session into an asciinema v2 cast file.
+pub struct Recorder {
+    file: File,
+    started_at: Instant,
+    pending_output: Vec<u8>,
+}
+
+impl Recorder {
+    pub async fn create(path: &Path, header: &RecordingHeader) -> Result<Self, ApiError> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let mut file = File::create(path).await?;
+        file.write_all(format!("{}\n", serde_json::to_string(header)?).as_bytes())
+            .await?;
+
+        Ok(Self {
+            file,
+            started_at: Instant::now(),
+            pending_output: vec![],
+        })
+    }
+
+    pub async fn output(&mut self, bytes: &[u8]) -> Result<(), ApiError> {
+        self.pending_output.extend_from_slice(bytes);
+        let text = take_utf8(&mut self.pending_output);
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        self.write_event("o", &text).await
+    }
+
+    pub async fn input(&mut self, text: &str) -> Result<(), ApiError> {
+        self.write_event("i", text).await
+    }
+
+    pub async fn resize(&mut self, cols: u32, rows: u32) -> Result<(), ApiError> {
+        self.write_event("r", &format!("{}x{}", cols, rows)).await
+    }
+
+    pub async fn finish(mut self) -> Result<(), ApiError> {
+        if !self.pending_output.is_empty() {
+            let text = String::from_utf8_lossy(&self.pending_output).into_owned();
+            self.write_event("o", &text).await?;
+        }
+
+        Ok(self.file.flush().await?)
+    }
+
+    async fn write_event(&mut self, code: &str, data: &str) -> Result<(), ApiError> {
+        let event = json!([self.started_at.elapsed().as_secs_f64(), code, data]);
+
+        Ok(self
+            .file
+            .write_all(format!("{}\n", event).as_bytes())
+            .await?)
+    }
+}
+
+/// Takes the decodable part of `buffer`, leaving an incomplete trailing UTF-8
+/// sequence in place so that characters split across chunks are kept intact.
+fn take_utf8(buffer: &mut Vec<u8>) -> String {
+    let valid_up_to = match std::str::from_utf8(buffer) {
+        Err(error) if error.error_len().is_none() => error.valid_up_to(),
+        _ => buffer.len(),
+    };
+
+    let rest = buffer.split_off(valid_up_to);
+    let text = String::from_utf8_lossy(buffer).into_owned();
+    *buffer = rest;
+
+    text
+}
+
+pub fn recordings_directory(app: &AppHandle) -> Result<PathBuf, ApiError> {
+    Ok(app.path().app_data_dir()?.join("recordings"))
+}
+
+/// Resolves the cast file of recording `id`, rejecting ids that could escape
+/// the recordings directory.
+pub fn recording_path(app: &AppHandle, id: &str) -> Result<PathBuf, ApiError> {
+    if id.is_empty()
+        || !id.chars().all(|character| {
+            character.is_ascii_alphanumeric() || character == '-' || character == '_'
+        })
+    {
+        return Err(ApiError::NotFound {
+            item: format!("recording {}", id),
+        });
+    }
+
+    Ok(recordings_directory(app)?.join(format!("{}.{}", id, RECORDING_EXTENSION)))
+}
+
+pub fn recording_id(path: &Path) -> Option<String> {
+    if path.extension()? != RECORDING_EXTENSION {
+        return None;
+    }
+
+    Some(path.file_stem()?.to_string_lossy().into_owned())
+}
@@ -0,0 +1,168 @@
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum State {
+    #[default]
+    Ground,
+    Escape,
+    EscapeIntermediate,
+    Csi,
+    /// OSC, DCS, SOS, PM and APC strings, terminated by BEL or ST.
+    String,
+    StringEscape,
+}
+
+/// Turns a terminal output stream into plain text lines. Escape sequences are
+/// dropped, backspaces erase and a carriage return not followed by a line
+/// feed overwrites the current line. The state is kept across chunks so that
+/// sequences split between two reads are still recognised.
+#[derive(Default)]
+pub struct AnsiStripper {
+    state: State,
+    line: Vec<u8>,
+    carriage_return: bool,
+}
+
+impl AnsiStripper {
+    /// Feeds `bytes` and returns the lines completed by them.
+    pub fn push(&mut self, bytes: &[u8]) -> String {
+        let mut text = String::new();
+
+        for &byte in bytes {
+            self.state = match self.state {
+                State::Ground => self.ground(byte, &mut text),
+                State::Escape => match byte {
+                    b'[' => State::Csi,
+                    b']' | b'P' | b'X' | b'^' | b'_' => State::String,
+                    0x20..=0x2f => State::EscapeIntermediate,
+                    _ => State::Ground,
+                },
+                State::EscapeIntermediate => match byte {
+                    0x20..=0x2f => State::EscapeIntermediate,
+                    _ => State::Ground,
+                },
+                State::Csi => match byte {
+                    0x40..=0x7e => State::Ground,
+                    0x1b => State::Escape,
+                    _ => State::Csi,
+                },
+                State::String => match byte {
+                    0x07 => State::Ground,
+                    0x1b => State::StringEscape,
+                    _ => State::String,
+                },
+                State::StringEscape => match byte {
+                    b'\\' => State::Ground,
+                    0x1b => State::StringEscape,
+                    _ => State::String,
+                },
+            };
+        }
+
+        text
+    }
+
+    /// Returns the unterminated last line, if any.
+    pub fn flush(&mut self) -> String {
+        if self.line.is_empty() {
+            return String::new();
+        }
+
+        let mut text = String::from_utf8_lossy(&self.line).into_owned();
+        text.push('\n');
+        self.line.clear();
+        self.carriage_return = false;
+
+        text
+    }
+
+    fn ground(&mut self, byte: u8, text: &mut String) -> State {
+        if self.carriage_return && byte != b'\n' && byte != b'\r' && !byte.is_ascii_control() {
+            self.line.clear();
+        }
+        if byte != 0x1b {
+            self.carriage_return = false;
+        }
+
+        match byte {
+            0x1b => return State::Escape,
+            b'\n' => {
+                text.push_str(&String::from_utf8_lossy(&self.line));
+                text.push('\n');
+                self.line.clear();
+            }
+            b'\r' => self.carriage_return = true,
+            0x08 => self.erase_character(),
+            b'\t' => self.line.push(byte),
+            0x00..=0x1f | 0x7f => {}
+            _ => self.line.push(byte),
+        }
+
+        State::Ground
+    }
+
+    fn erase_character(&mut self) {
+        // Drop UTF-8 continuation bytes together with their leading byte.
+        while let Some(byte) = self.line.pop() {
+            if byte & 0xc0 != 0x80 {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strip(chunks: &[&[u8]]) -> String {
+        let mut stripper = AnsiStripper::default();
+        let mut text = chunks
+            .iter()
+            .map(|chunk| stripper.push(chunk))
+            .collect::<String>();
+        text.push_str(&stripper.flush());
+        text
+    }
+
+    #[test]
+    fn drops_csi_sequences() {
+        assert_eq!(strip(&[b"\x1b[1;31mred\x1b[0m text\n"]), "red text\n");
+    }
+
+    #[test]
+    fn drops_csi_sequences_split_across_chunks() {
+        assert_eq!(strip(&[b"ab\x1b", b"[3", b"1mcd\n"]), "abcd\n");
+        assert_eq!(strip(&[b"ab\x1b[38;5;", b"196mcd\n"]), "abcd\n");
+    }
+
+    #[test]
+    fn drops_osc_sequences_split_across_chunks() {
+        assert_eq!(strip(&[b"\x1b]0;ti", b"tle\x07prompt$ \n"]), "prompt$ \n");
+        assert_eq!(strip(&[b"\x1b]0;title\x1b", b"\\prompt$ \n"]), "prompt$ \n");
+    }
+
+    #[test]
+    fn carriage_return_overwrites_the_line() {
+        assert_eq!(
+            strip(&[b"progress 10%\rprogress 100%\n"]),
+            "progress 100%\n"
+        );
+        assert_eq!(strip(&[b"progress 10%\r", b"done\n"]), "done\n");
+        assert_eq!(strip(&[b"line\r\n"]), "line\n");
+    }
+
+    #[test]
+    fn backspace_erases_multibyte_characters() {
+        assert_eq!(strip(&["h\u{e9}\x08llo\n".as_bytes()]), "hllo\n");
+        assert_eq!(strip(&["a\u{20ac}\x08b\n".as_bytes()]), "ab\n");
+        assert_eq!(strip(&["\u{1f600}\x08\x08ok\n".as_bytes()]), "ok\n");
+    }
+
+    #[test]
+    fn flushes_the_unterminated_line() {
+        let mut stripper = AnsiStripper::default();
+
+        assert_eq!(stripper.push(b"prompt$ "), "");
+        assert_eq!(stripper.flush(), "prompt$ \n");
+        assert_eq!(stripper.flush(), "");
+    }
+}
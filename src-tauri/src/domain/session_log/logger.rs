@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+use crate::domain::host::event::SessionExit;
+use crate::domain::session_log::ansi::AnsiStripper;
+use crate::domain::session_log::models::{format_timestamp, SessionLogHeader, SessionLogSettings};
+use crate::infrastructure::error::ApiError;
+
+/// Writes the output of a terminal session as plain text, rotating the file
+/// once it grows past the configured size.
+pub struct SessionLogger {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    part: usize,
+    settings: SessionLogSettings,
+    header: SessionLogHeader,
+    stripper: AnsiStripper,
+}
+
+impl SessionLogger {
+    pub async fn create(
+        directory: &Path,
+        settings: SessionLogSettings,
+        header: SessionLogHeader,
+    ) -> Result<Self, ApiError> {
+        tokio::fs::create_dir_all(directory).await?;
+
+        let path = directory.join(settings.file_name(&header));
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+
+        let mut logger = Self {
+            path,
+            file,
+            written: 0,
+            part: 1,
+            settings,
+            header,
+            stripper: AnsiStripper::default(),
+        };
+        logger.write_header().await?;
+
+        Ok(logger)
+    }
+
+    pub async fn output(&mut self, bytes: &[u8]) -> Result<(), ApiError> {
+        let text = self.stripper.push(bytes);
+        self.write(&text).await
+    }
+
+    pub async fn finish(mut self, session_exit: &SessionExit) -> Result<(), ApiError> {
+        let text = self.stripper.flush();
+        self.write(&text).await?;
+
+        let exit = match (session_exit.exit_status, &session_exit.exit_signal) {
+            (_, Some(exit_signal)) => format!("# Exit signal: {}", exit_signal),
+            (Some(exit_status), None) => format!("# Exit status: {}", exit_status),
+            (None, None) => "# Exit status: unknown".to_string(),
+        };
+        let footer = format!("\n# Ended: {}\n{}\n", format_timestamp(unix_now()), exit);
+        self.file.write_all(footer.as_bytes()).await?;
+
+        Ok(self.file.flush().await?)
+    }
+
+    async fn write(&mut self, text: &str) -> Result<(), ApiError> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let max_file_size = self.settings.max_file_size();
+        if max_file_size > 0 && self.written + text.len() as u64 > max_file_size {
+            self.rotate().await?;
+        }
+
+        self.file.write_all(text.as_bytes()).await?;
+        self.written += text.len() as u64;
+
+        Ok(())
+    }
+
+    async fn write_header(&mut self) -> Result<(), ApiError> {
+        let header = self.header.to_lines(self.part);
+        self.file.write_all(header.as_bytes()).await?;
+        self.written = header.len() as u64;
+
+        Ok(())
+    }
+
+    /// Shifts `name.N` to `name.N+1`, moves the current log to `name.1` and
+    /// starts a new part, dropping the files beyond `max_files`.
+    async fn rotate(&mut self) -> Result<(), ApiError> {
+        self.file.flush().await?;
+
+        let max_files = self.settings.max_files();
+        for index in (1..max_files).rev() {
+            let rotated = self.rotated_path(index);
+            if tokio::fs::try_exists(&rotated).await? {
+                tokio::fs::rename(&rotated, self.rotated_path(index + 1)).await?;
+            }
+        }
+        tokio::fs::rename(&self.path, self.rotated_path(1)).await?;
+
+        self.file = File::create(&self.path).await?;
+        self.part += 1;
+        self.write_header().await
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut file_name = self.path.file_name().unwrap_or_default().to_os_string();
+        file_name.push(format!(".{}", index));
+        self.path.with_file_name(file_name)
+    }
+}
+
+pub fn session_logs_directory(
+    app: &AppHandle,
+    settings: &SessionLogSettings,
+) -> Result<PathBuf, ApiError> {
+    match settings
+        .directory
+        .as_deref()
+        .filter(|directory| !directory.trim().is_empty())
+    {
+        Some(directory) => Ok(PathBuf::from(directory)),
+        None => Ok(app.path().app_data_dir()?.join("session-logs")),
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
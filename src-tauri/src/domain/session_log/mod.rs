@@ -0,0 +1,3 @@
+pub mod ansi;
+pub mod logger;
+pub mod models;
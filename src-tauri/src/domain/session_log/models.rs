@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+
+use crate::infrastructure::error::ApiError;
+
+const DEFAULT_FILE_NAME_PATTERN: &str = "{host}-{date}-{time}.log";
+
+const DEFAULT_MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+const DEFAULT_MAX_FILES: usize = 5;
+
+/// Global session log preferences. The file name pattern understands the
+/// `{host}`, `{address}`, `{user}`, `{date}`, `{time}` and `{timestamp}`
+/// placeholders.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLogSettings {
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default)]
+    pub file_name_pattern: Option<String>,
+    #[serde(default)]
+    pub max_file_size: Option<u64>,
+    #[serde(default)]
+    pub max_files: Option<usize>,
+}
+
+impl SessionLogSettings {
+    /// Bytes after which the log is rotated, 0 disables rotation.
+    pub fn max_file_size(&self) -> u64 {
+        self.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE)
+    }
+
+    /// Rotated files kept next to the current log. At least one is kept, as
+    /// rotating into no file would truncate the current log instead.
+    pub fn max_files(&self) -> usize {
+        self.max_files.unwrap_or(DEFAULT_MAX_FILES).max(1)
+    }
+
+    pub fn validate(&self) -> Result<(), ApiError> {
+        if self.max_files == Some(0) {
+            return Err(ApiError::InvalidSessionLogSettings {
+                reason: "at least one rotated file must be kept".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    pub fn file_name(&self, header: &SessionLogHeader) -> String {
+        let (date, time) = format_utc(header.started_at);
+        let pattern = self
+            .file_name_pattern
+            .as_deref()
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .unwrap_or(DEFAULT_FILE_NAME_PATTERN);
+
+        let file_name = pattern
+            .replace("{host}", &header.host)
+            .replace("{address}", &header.address)
+            .replace("{user}", &header.username)
+            .replace("{date}", &date)
+            .replace("{time}", &time.replace(':', ""))
+            .replace("{timestamp}", &header.started_at.to_string());
+
+        let file_name = sanitize_file_name(&file_name);
+        if file_name.is_empty() {
+            format!("{}.log", header.started_at)
+        } else {
+            file_name
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionLogHeader {
+    pub host: String,
+    pub address: String,
+    pub username: String,
+    pub started_at: u64,
+}
+
+impl SessionLogHeader {
+    pub fn to_lines(&self, part: usize) -> String {
+        let mut lines = vec![
+            format!("# Host: {} ({})", self.host, self.address),
+            format!("# User: {}", self.username),
+            format!("# Started: {}", format_timestamp(self.started_at)),
+        ];
+        if part > 1 {
+            lines.push(format!("# Part: {}", part));
+        }
+        lines.push(String::new());
+
+        lines.join("\n") + "\n"
+    }
+}
+
+/// Formats a unix timestamp as an ISO 8601 UTC date time.
+pub fn format_timestamp(timestamp: u64) -> String {
+    let (date, time) = format_utc(timestamp);
+    format!("{}T{}Z", date, time)
+}
+
+/// Splits a unix timestamp into a `YYYY-MM-DD` date and a `HH:MM:SS` time,
+/// using the days-to-civil conversion of the proleptic Gregorian calendar.
+fn format_utc(timestamp: u64) -> (String, String) {
+    let days = (timestamp / 86_400) as i64;
+    let seconds = timestamp % 86_400;
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (
+        format!("{:04}-{:02}-{:02}", year, month, day),
+        format!(
+            "{:02}:{:02}:{:02}",
+            seconds / 3_600,
+            seconds % 3_600 / 60,
+            seconds % 60
+        ),
+    )
+}
+
+fn sanitize_file_name(file_name: &str) -> String {
+    file_name
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() || matches!(character, '-' | '_' | '.' | '@') {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_start_matches('.')
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_the_epoch() {
+        assert_eq!(
+            format_utc(0),
+            ("1970-01-01".to_string(), "00:00:00".to_string())
+        );
+    }
+
+    #[test]
+    fn formats_leap_days() {
+        assert_eq!(
+            format_utc(951_782_400),
+            ("2000-02-29".to_string(), "00:00:00".to_string())
+        );
+        assert_eq!(
+            format_utc(951_868_799),
+            ("2000-02-29".to_string(), "23:59:59".to_string())
+        );
+        assert_eq!(
+            format_utc(951_868_800),
+            ("2000-03-01".to_string(), "00:00:00".to_string())
+        );
+    }
+
+    #[test]
+    fn formats_timestamps_past_2038() {
+        assert_eq!(
+            format_utc(2_147_483_647),
+            ("2038-01-19".to_string(), "03:14:07".to_string())
+        );
+        assert_eq!(
+            format_utc(2_147_483_648),
+            ("2038-01-19".to_string(), "03:14:08".to_string())
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::domain::host::models::{ConnectionSettings, TerminalSettings};
+use crate::domain::session_log::models::SessionLogSettings;
 use crate::domain::setting::event::DownloadEvent;
 use crate::domain::setting::models::{Settings, UpdateInformation};
 use crate::domain::store::r#enum::StoreKey;
@@ -24,6 +26,9 @@ pub async fn get_settings(state: State<'_, Mutex<AppData>>) -> Result<Response,
 pub async fn update_settings(
     state: State<'_, Mutex<AppData>>,
     gpt_api_key: String,
+    connection_settings: Option<ConnectionSettings>,
+    terminal_settings: Option<TerminalSettings>,
+    session_log_settings: Option<SessionLogSettings>,
 ) -> Result<Response, ApiError> {
     log::debug!("update_settings called");
     let store_manager = &state.lock().await.store_manager;
@@ -31,6 +36,18 @@ pub async fn update_settings(
     let mut settings = store_manager.get_data::<Settings>(StoreKey::Settings)?;
 
     settings.gpt_api_key = gpt_api_key;
+    if let Some(connection_settings) = connection_settings {
+        connection_settings.client_config()?;
+        settings.connection_settings = connection_settings;
+    }
+    if let Some(terminal_settings) = terminal_settings {
+        terminal_settings.terminal_modes()?;
+        settings.terminal_settings = terminal_settings;
+    }
+    if let Some(session_log_settings) = session_log_settings {
+        session_log_settings.validate()?;
+        settings.session_log_settings = session_log_settings;
+    }
 
     store_manager.update_data(StoreKey::Settings, settings)?;
 
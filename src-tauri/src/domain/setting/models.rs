@@ -1,15 +1,27 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domain::host::models::{ConnectionSettings, TerminalSettings};
+use crate::domain::session_log::models::SessionLogSettings;
+
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Settings {
     pub gpt_api_key: String,
+    #[serde(default)]
+    pub connection_settings: ConnectionSettings,
+    #[serde(default)]
+    pub terminal_settings: TerminalSettings,
+    #[serde(default)]
+    pub session_log_settings: SessionLogSettings,
 }
 
 impl Settings {
     pub fn default() -> Self {
         Self {
             gpt_api_key: String::new(),
+            connection_settings: ConnectionSettings::default(),
+            terminal_settings: TerminalSettings::default(),
+            session_log_settings: SessionLogSettings::default(),
         }
     }
 }
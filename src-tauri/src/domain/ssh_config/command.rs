@@ -0,0 +1,434 @@
+use crate::domain::host::models::{
+    ConnectionSettings, Credential, Host, StartupSettings, TerminalSettings,
+};
+use crate::domain::identity::models::Identity;
+use crate::domain::private_key::models::PrivateKey;
+use crate::domain::ssh_config::models::{SshConfigExportEntry, SshConfigImportEntry};
+use crate::domain::ssh_config::parser::{parse_jump_spec, SshConfig, SshConfigHost};
+use crate::domain::store::r#enum::StoreKey;
+use crate::domain::store::store_manager::StoreManager;
+use crate::infrastructure::app::AppData;
+use crate::infrastructure::error::ApiError;
+use crate::infrastructure::response::Response;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri;
+use tauri::{AppHandle, Manager, State};
+use tokio::sync::Mutex;
+
+#[tauri::command]
+pub async fn import_ssh_config(
+    app: AppHandle,
+    state: State<'_, Mutex<AppData>>,
+    path: Option<String>,
+    dry_run: bool,
+) -> Result<Response, ApiError> {
+    log::debug!("import_ssh_config called");
+
+    let home_dir = app.path().home_dir()?;
+    let path = path
+        .map(PathBuf::from)
+        .unwrap_or(home_dir.join(".ssh").join("config"));
+
+    let config = SshConfig::load(&path, &home_dir, &local_user())?;
+    let resolved_hosts = resolve_hosts(&config);
+
+    let store_manager = &state.lock().await.store_manager;
+
+    let existing_hosts = existing_host_keys(store_manager)?;
+
+    let mut entries = resolved_hosts
+        .iter()
+        .map(|(alias, resolved)| SshConfigImportEntry {
+            alias: alias.clone(),
+            address: resolved.host_name.clone(),
+            port: resolved.port,
+            username: resolved.user.clone(),
+            identity_files: resolved
+                .identity_files
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            jump_hosts: resolved.proxy_jump.clone(),
+            forward_agent: resolved.forward_agent,
+            existing_host_id: existing_hosts
+                .get(&(
+                    resolved.host_name.clone(),
+                    resolved.port,
+                    resolved.user.clone(),
+                ))
+                .cloned(),
+            host_id: None,
+        })
+        .collect::<Vec<SshConfigImportEntry>>();
+
+    if dry_run {
+        return Ok(Response::from_data(entries));
+    }
+
+    let mut hosts = store_manager.get_data::<Vec<Host>>(StoreKey::Hosts)?;
+    let mut identities = store_manager.get_data::<Vec<Identity>>(StoreKey::Identities)?;
+    let mut private_keys = store_manager.get_data::<Vec<PrivateKey>>(StoreKey::PrivateKeys)?;
+
+    // Aliases resolving to the same destination share the first host created
+    // for it, as if it had existed before the import.
+    let mut imported_hosts: HashMap<(String, u32, String), String> = HashMap::new();
+
+    for entry in entries.iter_mut() {
+        let key = (entry.address.clone(), entry.port, entry.username.clone());
+        if let Some(host_id) = imported_hosts.get(&key) {
+            entry.existing_host_id = Some(host_id.clone());
+        }
+        if let Some(ref existing_host_id) = entry.existing_host_id {
+            entry.host_id = Some(existing_host_id.clone());
+            continue;
+        }
+
+        let private_key_ref = entry
+            .identity_files
+            .iter()
+            .find_map(|path| import_private_key(&mut private_keys, Path::new(path)));
+
+        let identity_ref = if let Some(identity) = identities.iter().find(|identity| {
+            identity.username == entry.username && identity.private_key_ref == private_key_ref
+        }) {
+            identity.id.clone()
+        } else {
+            let identity = Identity::new(
+                Some(entry.username.clone()),
+                entry.username.clone(),
+                None,
+                private_key_ref,
+                true,
+                None,
+            );
+            identities.push(identity.clone());
+            identity.id
+        };
+
+        let host = Host::new(
+            Some(entry.alias.clone()),
+            entry.address.clone(),
+            entry.port,
+            Credential::Identity(identity_ref),
+            None,
+            vec![],
+            vec![],
+            ConnectionSettings::default(),
+            TerminalSettings::default(),
+            StartupSettings::default(),
+            entry.forward_agent,
+            false,
+        );
+        entry.host_id = Some(host.id.clone());
+        imported_hosts.insert(key, host.id.clone());
+        hosts.push(host);
+    }
+
+    let host_ids = entries
+        .iter()
+        .filter_map(|entry| Some((entry.alias.clone(), entry.host_id.clone()?)))
+        .collect::<HashMap<String, String>>();
+
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.existing_host_id.is_none())
+    {
+        if let Some(host) = hosts
+            .iter_mut()
+            .find(|host| Some(&host.id) == entry.host_id.as_ref())
+        {
+            host.jump_host_refs = entry
+                .jump_hosts
+                .iter()
+                .filter_map(|jump_host| host_ids.get(jump_host).cloned())
+                .collect();
+        }
+    }
+
+    store_manager.update_data(StoreKey::PrivateKeys, private_keys)?;
+    store_manager.update_data(StoreKey::Identities, identities)?;
+    store_manager.update_data(StoreKey::Hosts, hosts)?;
+
+    Ok(Response::from_data(entries))
+}
+
+#[tauri::command]
+pub async fn export_ssh_config(
+    state: State<'_, Mutex<AppData>>,
+    host_ids: Option<Vec<String>>,
+    path: Option<String>,
+    private_key_directory: Option<String>,
+) -> Result<Response, ApiError> {
+    log::debug!("export_ssh_config called");
+
+    let store_manager = state.lock().await.store_manager.clone();
+
+    let hosts = store_manager.get_data::<Vec<Host>>(StoreKey::Hosts)?;
+    let private_keys = store_manager.get_data::<Vec<PrivateKey>>(StoreKey::PrivateKeys)?;
+
+    let aliases = host_aliases(&hosts);
+
+    let mut selected_ids = host_ids.unwrap_or(hosts.iter().map(|host| host.id.clone()).collect());
+    let mut index = 0;
+    while index < selected_ids.len() {
+        if let Some(host) = hosts.iter().find(|host| host.id == selected_ids[index]) {
+            for jump_host_ref in host.jump_host_refs.iter() {
+                if !selected_ids.contains(jump_host_ref) {
+                    selected_ids.push(jump_host_ref.clone());
+                }
+            }
+        }
+        index += 1;
+    }
+
+    // Only the exported hosts need their credentials to resolve.
+    let credentials = hosts
+        .iter()
+        .filter(|host| selected_ids.contains(&host.id))
+        .map(|host| Ok((host.id.clone(), resolve_credential(&store_manager, host)?)))
+        .collect::<Result<HashMap<String, (String, Option<String>)>, ApiError>>()?;
+
+    let private_key_directory = private_key_directory.map(PathBuf::from);
+    if let Some(ref directory) = private_key_directory {
+        create_private_directory(directory).await?;
+    }
+
+    let mut entries = vec![];
+    for host in hosts.iter().filter(|host| selected_ids.contains(&host.id)) {
+        let (username, private_key_ref) = credentials[&host.id].clone();
+
+        let private_key = private_key_ref.and_then(|private_key_ref| {
+            private_keys
+                .iter()
+                .find(|private_key| private_key.id == private_key_ref)
+        });
+
+        let identity_file = match (&private_key_directory, private_key) {
+            (Some(directory), Some(private_key)) => {
+                let path = directory.join(format!(
+                    "{}-{}",
+                    sanitize_alias(&private_key.label),
+                    private_key.id
+                ));
+                write_private_file(&path, &private_key.content).await?;
+                Some(path.to_string_lossy().to_string())
+            }
+            _ => None,
+        };
+
+        entries.push(SshConfigExportEntry {
+            alias: aliases[&host.id].clone(),
+            label: host.label.clone().filter(|label| !label.is_empty()),
+            address: host.address.clone(),
+            port: host.port,
+            username,
+            identity_file,
+            jump_hosts: host
+                .jump_host_refs
+                .iter()
+                .filter_map(|jump_host_ref| aliases.get(jump_host_ref).cloned())
+                .collect(),
+            forward_agent: host.forward_agent,
+        });
+    }
+
+    let mut content = entries
+        .iter()
+        .map(SshConfigExportEntry::to_block)
+        .collect::<Vec<String>>()
+        .join("\n\n");
+    content.push('\n');
+
+    if let Some(path) = path {
+        let path = PathBuf::from(path);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        write_private_file(&path, &content).await?;
+    }
+
+    Ok(Response::from_value(
+        serde_json::json!({ "content": content }),
+    ))
+}
+
+/// Resolves every concrete alias along with the ProxyJump hops they reference,
+/// hops being resolved through the same configuration like OpenSSH does.
+fn resolve_hosts(config: &SshConfig) -> Vec<(String, SshConfigHost)> {
+    let mut resolved_hosts: Vec<(String, SshConfigHost)> = config
+        .aliases()
+        .iter()
+        .map(|alias| (alias.clone(), config.resolve(alias)))
+        .collect();
+
+    let mut index = 0;
+    while index < resolved_hosts.len() {
+        for jump_spec in resolved_hosts[index].1.proxy_jump.clone() {
+            if resolved_hosts.iter().any(|(alias, _)| alias == &jump_spec) {
+                continue;
+            }
+
+            let (user, host, port) = parse_jump_spec(&jump_spec);
+            let mut resolved = config.resolve(&host);
+            if let Some(user) = user {
+                resolved.user = user;
+            }
+            if let Some(port) = port {
+                resolved.port = port;
+            }
+            resolved_hosts.push((jump_spec, resolved));
+        }
+        index += 1;
+    }
+
+    resolved_hosts
+}
+
+fn existing_host_keys(
+    store_manager: &StoreManager,
+) -> Result<HashMap<(String, u32, String), String>, ApiError> {
+    let hosts = store_manager.get_data::<Vec<Host>>(StoreKey::Hosts)?;
+
+    Ok(hosts
+        .iter()
+        .filter_map(|host| {
+            let username = host.get_credential(store_manager).ok()?.username;
+            Some(((host.address.clone(), host.port, username), host.id.clone()))
+        })
+        .collect())
+}
+
+fn import_private_key(private_keys: &mut Vec<PrivateKey>, path: &Path) -> Option<String> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) => {
+            log::warn!("Skipping identity file {:?}: {}", path, err);
+            return None;
+        }
+    };
+
+    if let Some(private_key) = private_keys
+        .iter()
+        .find(|private_key| private_key.content == content)
+    {
+        return Some(private_key.id.clone());
+    }
+
+    let label = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let certificate_path = PathBuf::from(format!("{}-cert.pub", path.to_string_lossy()));
+    let certificate = std::fs::read_to_string(certificate_path).ok();
+
+    let private_key = PrivateKey::new(label, content, None, certificate);
+    private_keys.push(private_key.clone());
+    Some(private_key.id)
+}
+
+fn local_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default()
+}
+
+fn resolve_credential(
+    store_manager: &StoreManager,
+    host: &Host,
+) -> Result<(String, Option<String>), ApiError> {
+    match &host.credential {
+        Credential::Local(local_auth) => Ok((
+            local_auth.username.clone(),
+            local_auth.private_key_ref.clone(),
+        )),
+        Credential::Identity(identity_ref) => {
+            match store_manager.get_item::<Identity>(StoreKey::Identities, identity_ref)? {
+                Some(identity) => Ok((identity.username, identity.private_key_ref)),
+                None => Err(ApiError::NotFound {
+                    item: identity_ref.to_string(),
+                }),
+            }
+        }
+    }
+}
+
+/// Derives a unique `Host` alias for every host from its label, falling back
+/// to its address.
+fn host_aliases(hosts: &[Host]) -> HashMap<String, String> {
+    let mut aliases = HashMap::new();
+    let mut used: Vec<String> = vec![];
+
+    for host in hosts {
+        let base = host
+            .label
+            .as_ref()
+            .map(|label| sanitize_alias(label))
+            .filter(|label| !label.is_empty())
+            .unwrap_or(sanitize_alias(&host.address));
+
+        let mut alias = base.clone();
+        let mut suffix = 2;
+        while used.contains(&alias) {
+            alias = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+
+        used.push(alias.clone());
+        aliases.insert(host.id.clone(), alias);
+    }
+    aliases
+}
+
+fn sanitize_alias(value: &str) -> String {
+    value
+        .trim()
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() || "-_.@".contains(character) {
+                character
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+async fn create_private_directory(path: &Path) -> Result<(), ApiError> {
+    tokio::fs::create_dir_all(path).await?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o700)).await?;
+    }
+
+    Ok(())
+}
+
+/// Writes `content` readable by the owner only, as OpenSSH refuses private keys
+/// accessible by others.
+async fn write_private_file(path: &Path, content: &str) -> Result<(), ApiError> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Created owner only, so the key is never readable by others in between.
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let mut file = options.open(path).await?;
+
+    // An existing file keeps its mode when opened, it is narrowed before writing.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .await?;
+    }
+
+    file.write_all(content.as_bytes()).await?;
+    file.flush().await?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SshConfigImportEntry {
+    pub alias: String,
+    pub address: String,
+    pub port: u32,
+    pub username: String,
+    pub identity_files: Vec<String>,
+    pub jump_hosts: Vec<String>,
+    pub forward_agent: bool,
+    pub existing_host_id: Option<String>,
+    pub host_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SshConfigExportEntry {
+    pub alias: String,
+    pub label: Option<String>,
+    pub address: String,
+    pub port: u32,
+    pub username: String,
+    pub identity_file: Option<String>,
+    pub jump_hosts: Vec<String>,
+    pub forward_agent: bool,
+}
+
+impl SshConfigExportEntry {
+    pub fn to_block(&self) -> String {
+        let mut lines = vec![];
+        if let Some(label) = self.label.as_ref().filter(|label| **label != self.alias) {
+            lines.push(format!("# {}", label));
+        }
+        lines.push(format!("Host {}", self.alias));
+        lines.push(format!("    HostName {}", self.address));
+        if self.port != 22 {
+            lines.push(format!("    Port {}", self.port));
+        }
+        lines.push(format!("    User {}", quote(&self.username)));
+        if let Some(identity_file) = &self.identity_file {
+            lines.push(format!("    IdentityFile {}", quote(identity_file)));
+            lines.push("    IdentitiesOnly yes".to_string());
+        }
+        if !self.jump_hosts.is_empty() {
+            lines.push(format!("    ProxyJump {}", self.jump_hosts.join(",")));
+        }
+        if self.forward_agent {
+            lines.push("    ForwardAgent yes".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+fn quote(value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("\"{}\"", value)
+    } else {
+        value.to_string()
+    }
+}
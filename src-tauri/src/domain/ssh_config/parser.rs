@@ -0,0 +1,376 @@
+use crate::domain::known_host::models::match_wildcard;
+use crate::infrastructure::error::ApiError;
+use std::path::{Path, PathBuf};
+
+const MAX_INCLUDE_DEPTH: usize = 16;
+const DEFAULT_PORT: u32 = 22;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Criterion {
+    All,
+    Host(String),
+    OriginalHost(String),
+    User(String),
+    LocalUser(String),
+    Unsupported,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Host(Vec<String>),
+    Match(Vec<(bool, Criterion)>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Section {
+    condition: Condition,
+    options: Vec<(String, Vec<String>)>,
+}
+
+/// Effective settings of a single `Host` alias after applying every matching
+/// section with OpenSSH's first-value-wins rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SshConfigHost {
+    pub alias: String,
+    pub host_name: String,
+    pub port: u32,
+    pub user: String,
+    pub identity_files: Vec<PathBuf>,
+    pub proxy_jump: Vec<String>,
+    pub forward_agent: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SshConfig {
+    sections: Vec<Section>,
+    home_dir: PathBuf,
+    local_user: String,
+}
+
+impl SshConfig {
+    pub fn load(path: &Path, home_dir: &Path, local_user: &str) -> Result<Self, ApiError> {
+        let mut config = Self {
+            sections: vec![Section {
+                condition: Condition::Match(vec![(false, Criterion::All)]),
+                options: vec![],
+            }],
+            home_dir: home_dir.to_path_buf(),
+            local_user: local_user.to_string(),
+        };
+        config.read_file(path, 0)?;
+        Ok(config)
+    }
+
+    fn read_file(&mut self, path: &Path, depth: usize) -> Result<(), ApiError> {
+        if depth > MAX_INCLUDE_DEPTH {
+            log::warn!("Ignoring {:?}, too many nested includes", path);
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(path)?;
+
+        // Options after an `Include` still belong to the section of this file
+        // that contained it, not to the last section of the included file.
+        let mut current = self.sections.len() - 1;
+        for line in content.lines() {
+            let mut tokens = tokenize(line);
+            if tokens.is_empty() {
+                continue;
+            }
+            let keyword = tokens.remove(0).to_lowercase();
+
+            match keyword.as_str() {
+                "host" => {
+                    self.sections.push(Section {
+                        condition: Condition::Host(tokens),
+                        options: vec![],
+                    });
+                    current = self.sections.len() - 1;
+                }
+                "match" => {
+                    self.sections.push(Section {
+                        condition: Condition::Match(parse_criteria(tokens)),
+                        options: vec![],
+                    });
+                    current = self.sections.len() - 1;
+                }
+                "include" => {
+                    for pattern in tokens {
+                        for included in self.expand_include(&pattern)? {
+                            self.read_file(&included, depth + 1)?;
+                        }
+                    }
+                }
+                _ => self.sections[current].options.push((keyword, tokens)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves an `Include` argument relative to `~/.ssh`, expanding
+    /// wildcards in the file name.
+    fn expand_include(&self, pattern: &str) -> Result<Vec<PathBuf>, ApiError> {
+        let path = self.expand_path(pattern, None, None);
+        let path = if path.is_absolute() {
+            path
+        } else {
+            self.home_dir.join(".ssh").join(path)
+        };
+
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if !file_name.contains(['*', '?']) {
+            return Ok(if path.is_file() { vec![path] } else { vec![] });
+        }
+
+        let directory = match path.parent() {
+            Some(directory) if directory.is_dir() => directory,
+            _ => return Ok(vec![]),
+        };
+        let mut paths = std::fs::read_dir(directory)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| {
+                path.file_name()
+                    .map(|name| match_wildcard(&name.to_string_lossy(), &file_name))
+                    .unwrap_or(false)
+            })
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// Every alias declared on a `Host` line that names a single host.
+    pub fn aliases(&self) -> Vec<String> {
+        let mut aliases: Vec<String> = vec![];
+        for section in self.sections.iter() {
+            if let Condition::Host(patterns) = &section.condition {
+                for pattern in patterns {
+                    if pattern.contains(['*', '?', '!']) || aliases.contains(pattern) {
+                        continue;
+                    }
+                    aliases.push(pattern.clone());
+                }
+            }
+        }
+        aliases
+    }
+
+    pub fn resolve(&self, alias: &str) -> SshConfigHost {
+        let mut host_name: Option<String> = None;
+        let mut port: Option<u32> = None;
+        let mut user: Option<String> = None;
+        let mut identity_files: Vec<String> = vec![];
+        let mut proxy_jump: Option<Vec<String>> = None;
+        let mut forward_agent: Option<bool> = None;
+
+        for section in self.sections.iter() {
+            let applies = match &section.condition {
+                Condition::Host(patterns) => match_pattern_list(alias, patterns),
+                Condition::Match(criteria) => criteria.iter().all(|(negated, criterion)| {
+                    let matched = match criterion {
+                        Criterion::All => true,
+                        Criterion::Host(patterns) => {
+                            match_list(host_name.as_deref().unwrap_or(alias), patterns)
+                        }
+                        Criterion::OriginalHost(patterns) => match_list(alias, patterns),
+                        Criterion::User(patterns) => {
+                            match_list(user.as_deref().unwrap_or(&self.local_user), patterns)
+                        }
+                        Criterion::LocalUser(patterns) => match_list(&self.local_user, patterns),
+                        Criterion::Unsupported => false,
+                    };
+                    matched != *negated
+                }),
+            };
+            if !applies {
+                continue;
+            }
+
+            for (keyword, arguments) in section.options.iter() {
+                let value = match arguments.first() {
+                    Some(value) => value.clone(),
+                    None => continue,
+                };
+                match keyword.as_str() {
+                    "hostname" if host_name.is_none() => host_name = Some(value),
+                    "port" if port.is_none() => port = value.parse().ok(),
+                    "user" if user.is_none() => user = Some(value),
+                    "identityfile" => identity_files.push(value),
+                    "proxyjump" if proxy_jump.is_none() => {
+                        proxy_jump = Some(if value.eq_ignore_ascii_case("none") {
+                            vec![]
+                        } else {
+                            value.split(',').map(str::to_string).collect()
+                        })
+                    }
+                    "forwardagent" if forward_agent.is_none() => {
+                        forward_agent = Some(value.eq_ignore_ascii_case("yes"))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let host_name = host_name
+            .map(|host_name| host_name.replace("%h", alias))
+            .unwrap_or(alias.to_string());
+        let user = user.unwrap_or(self.local_user.clone());
+        let identity_files = identity_files
+            .iter()
+            .map(|identity_file| self.expand_path(identity_file, Some(&host_name), Some(&user)))
+            .collect();
+
+        SshConfigHost {
+            alias: alias.to_string(),
+            host_name,
+            port: port.unwrap_or(DEFAULT_PORT),
+            user,
+            identity_files,
+            proxy_jump: proxy_jump.unwrap_or_default(),
+            forward_agent: forward_agent.unwrap_or(false),
+        }
+    }
+
+    fn expand_path(&self, path: &str, host_name: Option<&str>, user: Option<&str>) -> PathBuf {
+        let home_dir = self.home_dir.to_string_lossy();
+        let mut expanded = String::new();
+        let mut characters = path.chars().peekable();
+
+        if path.starts_with("~/") || path == "~" {
+            expanded.push_str(&home_dir);
+            characters.next();
+        }
+
+        while let Some(character) = characters.next() {
+            if character != '%' {
+                expanded.push(character);
+                continue;
+            }
+            match characters.next() {
+                Some('%') => expanded.push('%'),
+                Some('d') => expanded.push_str(&home_dir),
+                Some('u') => expanded.push_str(&self.local_user),
+                Some('h') => expanded.push_str(host_name.unwrap_or_default()),
+                Some('r') => expanded.push_str(user.unwrap_or_default()),
+                Some(other) => {
+                    expanded.push('%');
+                    expanded.push(other);
+                }
+                None => expanded.push('%'),
+            }
+        }
+        PathBuf::from(expanded)
+    }
+}
+
+/// Parses a `[user@]host[:port]` ProxyJump hop.
+pub fn parse_jump_spec(spec: &str) -> (Option<String>, String, Option<u32>) {
+    let (user, rest) = match spec.rsplit_once('@') {
+        Some((user, rest)) => (Some(user.to_string()), rest),
+        None => (None, spec),
+    };
+
+    if let Some(rest) = rest.strip_prefix('[') {
+        if let Some((host, port)) = rest.split_once(']') {
+            return (
+                user,
+                host.to_string(),
+                port.strip_prefix(':').and_then(|port| port.parse().ok()),
+            );
+        }
+    }
+
+    match rest.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => (user, host.to_string(), port.parse().ok()),
+        _ => (user, rest.to_string(), None),
+    }
+}
+
+fn parse_criteria(tokens: Vec<String>) -> Vec<(bool, Criterion)> {
+    let mut criteria = vec![];
+    let mut tokens = tokens.into_iter();
+
+    while let Some(token) = tokens.next() {
+        let lowered = token.to_lowercase();
+        let (negated, name) = match lowered.strip_prefix('!') {
+            Some(name) => (true, name.to_string()),
+            None => (false, lowered),
+        };
+
+        let criterion = match name.as_str() {
+            "all" | "canonical" | "final" => Criterion::All,
+            "host" => Criterion::Host(tokens.next().unwrap_or_default()),
+            "originalhost" => Criterion::OriginalHost(tokens.next().unwrap_or_default()),
+            "user" => Criterion::User(tokens.next().unwrap_or_default()),
+            "localuser" => Criterion::LocalUser(tokens.next().unwrap_or_default()),
+            _ => {
+                tokens.next();
+                Criterion::Unsupported
+            }
+        };
+        criteria.push((negated, criterion));
+    }
+    criteria
+}
+
+fn match_list(name: &str, list: &str) -> bool {
+    match_pattern_list(
+        name,
+        &list.split(',').map(str::to_string).collect::<Vec<String>>(),
+    )
+}
+
+fn match_pattern_list(name: &str, patterns: &[String]) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(pattern) if match_wildcard(name, pattern) => return false,
+            Some(_) => {}
+            None if match_wildcard(name, pattern) => matched = true,
+            None => {}
+        }
+    }
+    matched
+}
+
+/// Splits a configuration line into its keyword and arguments, accepting both
+/// `Keyword value` and `Keyword=value` forms as well as double quotes.
+fn tokenize(line: &str) -> Vec<String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return vec![];
+    }
+
+    let mut tokens = vec![];
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut seen_equals = false;
+
+    for character in line.chars() {
+        match character {
+            '"' => in_quotes = !in_quotes,
+            '=' if !in_quotes
+                && !seen_equals
+                && (tokens.is_empty() || (tokens.len() == 1 && current.is_empty())) =>
+            {
+                seen_equals = true;
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
@@ -8,6 +8,7 @@ pub enum StoreKey {
     Hosts,
     Identities,
     PrivateKeys,
+    KnownHosts,
     Settings,
 }
 
@@ -17,6 +18,7 @@ impl StoreKey {
             StoreKey::Hosts => "hosts",
             StoreKey::Identities => "identities",
             StoreKey::PrivateKeys => "private_keys",
+            StoreKey::KnownHosts => "known_hosts",
             StoreKey::Settings => "settings",
         }
     }
@@ -28,6 +30,7 @@ pub fn default_settings() -> HashMap<String, Value> {
     defaults.insert(StoreKey::Hosts.as_str().to_string(), json!([]));
     defaults.insert(StoreKey::Identities.as_str().to_string(), json!([]));
     defaults.insert(StoreKey::PrivateKeys.as_str().to_string(), json!([]));
+    defaults.insert(StoreKey::KnownHosts.as_str().to_string(), json!([]));
     defaults.insert(
         StoreKey::Settings.as_str().to_string(),
         json!(Settings::default()),
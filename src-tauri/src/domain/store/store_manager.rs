@@ -23,6 +23,9 @@ impl StoreManager {
         if !store.has(StoreKey::PrivateKeys.as_str()) {
             store.set(StoreKey::PrivateKeys.as_str(), json!([]));
         }
+        if !store.has(StoreKey::KnownHosts.as_str()) {
+            store.set(StoreKey::KnownHosts.as_str(), json!([]));
+        }
         if !store.has(StoreKey::Settings.as_str()) {
             store.set(StoreKey::Settings.as_str(), json!(Settings::default()));
         }
@@ -1,8 +1,11 @@
+use crate::domain::broadcast::broadcast_manager::BroadcastManager;
 use crate::domain::future::future_manager::FutureManager;
+use crate::domain::host::sftp_manager::SftpManager;
 use crate::domain::store::store_manager::StoreManager;
 
-#[derive(Clone)]
 pub struct AppData {
     pub store_manager: StoreManager,
     pub future_manager: FutureManager,
+    pub sftp_manager: SftpManager,
+    pub broadcast_manager: BroadcastManager,
 }
@@ -32,6 +32,12 @@ pub enum ApiError {
     #[error(transparent)]
     RusshKey(#[from] russh::keys::Error),
 
+    #[error(transparent)]
+    RusshAgentAuth(#[from] russh::AgentAuthError),
+
+    #[error(transparent)]
+    RusshSftp(#[from] russh_sftp::client::error::Error),
+
     #[error(transparent)]
     OpenAI(#[from] OpenAIError),
 
@@ -43,6 +49,36 @@ pub enum ApiError {
 
     #[error("Session not found")]
     SessionNotFound,
+
+    #[error("The private key {label} is encrypted and no valid passphrase was provided")]
+    PrivateKeyEncrypted { label: String },
+
+    #[error("The certificate of {label} does not certify its private key")]
+    CertificateKeyMismatch { label: String },
+
+    #[error("Unsupported algorithm {name}")]
+    UnsupportedAlgorithm { name: String },
+
+    #[error("Unsupported terminal mode {name}")]
+    UnsupportedTerminalMode { name: String },
+
+    #[error("Connection timed out")]
+    ConnectionTimeout,
+
+    #[error("Command timed out")]
+    CommandTimeout,
+
+    #[error("Invalid TOTP secret: {reason}")]
+    InvalidTotpSecret { reason: String },
+
+    #[error("Invalid broadcast group: {reason}")]
+    InvalidBroadcastGroup { reason: String },
+
+    #[error("Invalid session log settings: {reason}")]
+    InvalidSessionLogSettings { reason: String },
+
+    #[error("Invalid SOCKS request: {reason}")]
+    InvalidSocksRequest { reason: String },
 }
 
 impl serde::Serialize for ApiError {
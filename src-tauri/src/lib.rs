@@ -1,24 +1,43 @@
 mod domain;
 mod infrastructure;
 
+use crate::domain::broadcast::broadcast_manager::BroadcastManager;
+use crate::domain::broadcast::command::{
+    add_broadcast_member, create_broadcast_group, delete_broadcast_group, list_broadcast_groups,
+    remove_broadcast_member, set_broadcast_member_paused,
+};
 use crate::domain::future::commands::stop_future;
 use crate::domain::future::future_manager::FutureManager;
 use crate::domain::gpt::command::get_agent_response;
 use crate::domain::host::commands::{
-    add_host, delete_host, list_hosts, start_terminal_stream, update_host, update_host_fingerprint,
+    add_host, close_sftp_session, create_sftp_directory, delete_host, delete_sftp_path,
+    exec_command, list_hosts, list_sftp_directory, read_sftp_file, rename_sftp_path,
+    start_batch_exec, start_exec_stream, start_sftp_session, start_terminal_stream, stat_sftp_path,
+    update_host, update_host_fingerprint, write_sftp_file,
 };
+use crate::domain::host::sftp_manager::SftpManager;
 use crate::domain::identity::command::{
-    add_identity, delete_identity, list_identities, update_identity,
+    add_identity, delete_identity, get_totp_code, list_identities, update_identity,
+};
+use crate::domain::known_host::command::{
+    delete_known_host, export_known_hosts, import_known_hosts, list_known_hosts,
 };
 use crate::domain::private_key::command::{
-    add_private_key, delete_private_key, list_private_keys, update_private_key,
+    add_private_key, delete_private_key, get_certificate_info, list_private_keys,
+    update_private_key,
+};
+use crate::domain::recording::command::{
+    delete_recording, export_recording, list_recordings, play_recording,
 };
 use crate::domain::setting::command::{
     apply_update, check_update, clear_data, get_settings, update_settings,
 };
+use crate::domain::ssh_config::command::{export_ssh_config, import_ssh_config};
 use crate::domain::store::store_manager::StoreManager;
 use crate::infrastructure::app::AppData;
-use domain::host::commands::start_tunnel_stream;
+use domain::host::commands::{
+    start_dynamic_tunnel_stream, start_reverse_tunnel_stream, start_tunnel_stream,
+};
 use domain::store::r#enum::default_settings;
 use tauri::Manager;
 use tokio::sync::Mutex;
@@ -44,6 +63,8 @@ pub fn run() {
             app.manage(Mutex::new(AppData {
                 store_manager: StoreManager::new(store),
                 future_manager: FutureManager::new(),
+                sftp_manager: SftpManager::new(),
+                broadcast_manager: BroadcastManager::new(),
             }));
 
             Ok(())
@@ -57,16 +78,53 @@ pub fn run() {
             update_host_fingerprint,
             start_terminal_stream,
             start_tunnel_stream,
+            start_reverse_tunnel_stream,
+            start_dynamic_tunnel_stream,
+            exec_command,
+            start_exec_stream,
+            start_batch_exec,
+            // Broadcast
+            list_broadcast_groups,
+            create_broadcast_group,
+            delete_broadcast_group,
+            add_broadcast_member,
+            remove_broadcast_member,
+            set_broadcast_member_paused,
+            // SFTP
+            start_sftp_session,
+            close_sftp_session,
+            list_sftp_directory,
+            stat_sftp_path,
+            read_sftp_file,
+            write_sftp_file,
+            rename_sftp_path,
+            delete_sftp_path,
+            create_sftp_directory,
             // Identity
             add_identity,
             delete_identity,
             list_identities,
             update_identity,
+            get_totp_code,
             // Private Key
             add_private_key,
             delete_private_key,
             list_private_keys,
             update_private_key,
+            get_certificate_info,
+            // Known Host
+            list_known_hosts,
+            delete_known_host,
+            import_known_hosts,
+            export_known_hosts,
+            // SSH Config
+            import_ssh_config,
+            export_ssh_config,
+            // Recording
+            list_recordings,
+            delete_recording,
+            export_recording,
+            play_recording,
             // GPT
             get_agent_response,
             // Setting
@@ -3,8 +3,20 @@ import { Credential, Host } from "@/types/interfaces";
 import { OKMessage } from "@/types/response";
 
 class HostService {
-  starTerminalStream = async (hostId: string, eventId: string) => {
-    return invoker<OKMessage>("start_terminal_stream", { hostId, eventId });
+  starTerminalStream = async (
+    hostId: string,
+    eventId: string,
+    cols?: number,
+    rows?: number,
+    record?: boolean,
+  ) => {
+    return invoker<OKMessage>("start_terminal_stream", {
+      hostId,
+      eventId,
+      cols,
+      rows,
+      record,
+    });
   };
 
   startTunnelStream = async (
@@ -63,8 +75,16 @@ class HostService {
     });
   };
 
-  updateFingerprint = async (id: string, fingerprint: string) => {
-    return invoker<OKMessage>("update_host_fingerprint", { id, fingerprint });
+  updateFingerprint = async (
+    id: string,
+    fingerprint: string,
+    eventId: string,
+  ) => {
+    return invoker<OKMessage>("update_host_fingerprint", {
+      id,
+      fingerprint,
+      eventId,
+    });
   };
 }
 
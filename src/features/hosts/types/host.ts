@@ -9,6 +9,7 @@ export interface LocalAuth {
     username: string;
     password?: string;
     privateKeyRef?: string;
+    useAgent?: boolean;
   };
 }
 
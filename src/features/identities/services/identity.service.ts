@@ -12,12 +12,16 @@ class IdentityService {
     username: string,
     password: string,
     privateKeyRef: string,
+    useAgent?: boolean,
+    totp?: string,
   ) => {
     return invoker<OKMessage>("add_identity", {
       label,
       username,
       password,
       privateKeyRef,
+      useAgent,
+      totp,
     });
   };
 
@@ -27,6 +31,8 @@ class IdentityService {
     username: string,
     password: string,
     privateKeyRef: string,
+    useAgent?: boolean,
+    totp?: string,
   ) => {
     return invoker<OKMessage>("update_identity", {
       id,
@@ -34,9 +40,18 @@ class IdentityService {
       username,
       password,
       privateKeyRef,
+      useAgent,
+      totp,
     });
   };
 
+  getTotpCode = async (id: string) => {
+    return invoker<{ code: string; remainingSeconds: number }>(
+      "get_totp_code",
+      { id },
+    );
+  };
+
   delete = async (id: string) => {
     return invoker<OKMessage>("delete_identity", { id });
   };
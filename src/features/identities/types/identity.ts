@@ -4,4 +4,11 @@ export interface Identity {
   username: string;
   password: string;
   privateKeyRef: string;
+  useAgent?: boolean;
+  totp?: {
+    secret: string;
+    algorithm: "SHA1" | "SHA256" | "SHA512";
+    digits: number;
+    period: number;
+  };
 }
@@ -2,26 +2,51 @@ import { invoker } from "@/core";
 import { PrivateKey } from "@/types/interfaces";
 import { OKMessage } from "@/types/response";
 
+import { CertificateInfo } from "../types/private-key";
+
 class PrivateKeyService {
   list = async () => {
     return invoker<PrivateKey[]>("list_private_keys");
   };
 
-  add = async (label: string, content: string) => {
+  add = async (
+    label: string,
+    content: string,
+    passphrase?: string,
+    certificate?: string,
+  ) => {
     return invoker<PrivateKey>("add_private_key", {
       label,
       content,
+      passphrase,
+      certificate,
     });
   };
 
-  update = async (id: string, label: string, content: string) => {
+  update = async (
+    id: string,
+    label: string,
+    content: string,
+    passphrase?: string,
+    certificate?: string,
+    clearPassphrase?: boolean,
+    clearCertificate?: boolean,
+  ) => {
     return invoker<PrivateKey>("update_private_key", {
       id,
       label,
       content,
+      passphrase,
+      clearPassphrase,
+      certificate,
+      clearCertificate,
     });
   };
 
+  getCertificateInfo = async (id: string) => {
+    return invoker<CertificateInfo | null>("get_certificate_info", { id });
+  };
+
   delete = async (id: string) => {
     return invoker<OKMessage>("delete_private_key", { id });
   };
@@ -2,4 +2,14 @@ export interface PrivateKey {
   id: string;
   label: string;
   content: string;
+  passphrase?: string;
+  certificate?: string;
+}
+
+export interface CertificateInfo {
+  keyId: string;
+  principals: string[];
+  validAfter: number;
+  validBefore: number;
+  expired: boolean;
 }
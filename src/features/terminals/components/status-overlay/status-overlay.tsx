@@ -1,26 +1,52 @@
-import { Plus, RefreshCw, X } from "lucide-react";
+import { Check, Plus, RefreshCw, X } from "lucide-react";
+import { FormEvent, useEffect, useState } from "react";
 
 import { Button } from "@/shared/ui/button";
+import { Input } from "@/shared/ui/input";
 
-import { ERROR_STATUS, StatusType } from "../terminal-view/constant.ts";
+import {
+  ERROR_STATUS,
+  PUBLIC_KEY_STATUS,
+  StatusType,
+} from "../terminal-view/constant.ts";
+import { KeyboardInteractiveRequest } from "../terminal-view/interface.ts";
 
 interface StatusOverlayProps {
   fingerprint: string | null;
+  passphraseLabel: string | null;
+  keyboardInteractiveRequest: KeyboardInteractiveRequest | null;
   open: boolean;
   status: StatusType;
   onReconnect: () => void;
   onClose: () => void;
   onPublicKeyConfirm: (_confirm: boolean) => void;
+  onPassphraseSubmit: (_passphrase: string | null) => void;
+  onKeyboardInteractiveSubmit: (_answers: string[] | null) => void;
 }
 
 const StatusOverlay = ({
   fingerprint,
+  passphraseLabel,
+  keyboardInteractiveRequest,
   status,
   open,
   onReconnect,
   onClose,
   onPublicKeyConfirm,
+  onPassphraseSubmit,
+  onKeyboardInteractiveSubmit,
 }: StatusOverlayProps) => {
+  const [passphrase, setPassphrase] = useState<string>("");
+  const [answers, setAnswers] = useState<string[]>([]);
+
+  useEffect(() => {
+    setPassphrase("");
+  }, [passphraseLabel]);
+
+  useEffect(() => {
+    setAnswers(keyboardInteractiveRequest?.prompts.map(() => "") ?? []);
+  }, [keyboardInteractiveRequest]);
+
   const handleReconnectClick = async () => {
     onReconnect();
   };
@@ -34,6 +60,31 @@ const StatusOverlay = ({
       onPublicKeyConfirm(confirm);
     }
   };
+
+  const handlePassphraseSubmit = (event: FormEvent<HTMLFormElement>) => {
+    event.preventDefault();
+    onPassphraseSubmit(passphrase);
+  };
+
+  const handleKeyboardInteractiveSubmit = (
+    event: FormEvent<HTMLFormElement>
+  ) => {
+    event.preventDefault();
+    onKeyboardInteractiveSubmit(answers);
+  };
+
+  const handleAnswerChange = (index: number, value: string) => {
+    setAnswers((answers) =>
+      answers.map((answer, i) => (i === index ? value : answer))
+    );
+  };
+
+  const isPassphrasePrompt =
+    status === StatusType._PassphraseRequired && passphraseLabel !== null;
+  const isKeyboardInteractivePrompt =
+    status === StatusType._KeyboardInteractivePrompt &&
+    keyboardInteractiveRequest !== null;
+
   return (
     <div
       className={`relative w-full h-full ${open ? "flex" : "hidden"} justify-center items-center flex-col`}
@@ -41,18 +92,32 @@ const StatusOverlay = ({
       <div className="absolute top-[35%]">
         {status === StatusType._NewPublicKeyFound ? (
           <p className="text-lg">New fingerprint found!!</p>
+        ) : status === StatusType._NewJumpHostPublicKeyFound ? (
+          <p className="text-lg">New jump host fingerprint found!!</p>
+        ) : isPassphrasePrompt ? (
+          <p className="text-lg">Passphrase required for {passphraseLabel}</p>
+        ) : isKeyboardInteractivePrompt ? (
+          <p className="text-lg">
+            {keyboardInteractiveRequest?.name || "Authentication required"}
+          </p>
         ) : (
           <p className="text-lg">{status}</p>
         )}
       </div>
       <div className="w-1/2">
-        {status === StatusType._NewPublicKeyFound && (
+        {PUBLIC_KEY_STATUS.includes(status) && (
           <p className="break-all font-mono text-sm">{fingerprint}</p>
         )}
+        {isKeyboardInteractivePrompt &&
+          keyboardInteractiveRequest?.instructions && (
+            <p className="whitespace-pre-wrap text-sm">
+              {keyboardInteractiveRequest?.instructions}
+            </p>
+          )}
       </div>
 
       <div className="mt-4 w-1/2">
-        {status === StatusType._NewPublicKeyFound && (
+        {PUBLIC_KEY_STATUS.includes(status) && (
           <div className="flex gap-2 w-full">
             <Button
               variant="destructive"
@@ -72,6 +137,71 @@ const StatusOverlay = ({
           </div>
         )}
 
+        {isPassphrasePrompt && (
+          <form
+            className="flex flex-col gap-2 w-full"
+            onSubmit={handlePassphraseSubmit}
+          >
+            <Input
+              type="password"
+              autoComplete="off"
+              autoFocus
+              value={passphrase}
+              onChange={(e) => setPassphrase(e.target.value)}
+            />
+            <div className="flex gap-2 w-full">
+              <Button
+                type="button"
+                variant="destructive"
+                className="flex-1"
+                onClick={() => onPassphraseSubmit(null)}
+              >
+                <X className="w-4 h-4 mr-2" />
+                Cancel
+              </Button>
+              <Button type="submit" className="flex-1">
+                <Check className="w-4 h-4 mr-2" />
+                Unlock
+              </Button>
+            </div>
+          </form>
+        )}
+
+        {isKeyboardInteractivePrompt && (
+          <form
+            className="flex flex-col gap-2 w-full"
+            onSubmit={handleKeyboardInteractiveSubmit}
+          >
+            {keyboardInteractiveRequest?.prompts.map((prompt, index) => (
+              <div key={index} className="flex flex-col gap-1">
+                <p className="text-sm">{prompt.prompt}</p>
+                <Input
+                  type={prompt.echo ? "text" : "password"}
+                  autoComplete="off"
+                  autoFocus={index === 0}
+                  value={answers[index] ?? ""}
+                  onChange={(e) => handleAnswerChange(index, e.target.value)}
+                />
+              </div>
+            ))}
+            <div className="flex gap-2 w-full">
+              <Button
+                type="button"
+                variant="destructive"
+                className="flex-1"
+                onClick={() => onKeyboardInteractiveSubmit(null)}
+              >
+                <X className="w-4 h-4 mr-2" />
+                Cancel
+              </Button>
+              <Button type="submit" className="flex-1">
+                <Check className="w-4 h-4 mr-2" />
+                Submit
+              </Button>
+            </div>
+          </form>
+        )}
+
         {ERROR_STATUS.includes(status) && (
           <div className="flex gap-2 w-full">
             <Button
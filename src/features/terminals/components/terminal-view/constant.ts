@@ -8,10 +8,28 @@ export enum StatusType {
   _ChannelOpened = "ChannelOpened",
   _StartStreaming = "StartStreaming",
   _NewPublicKeyFound = "NewPublicKeyFound",
+  _NewJumpHostPublicKeyFound = "NewJumpHostPublicKeyFound",
+  _HostKeyChanged = "HostKeyChanged",
+  _HostKeyRevoked = "HostKeyRevoked",
   _ConnectionFailed = "ConnectionFailed",
+  _PassphraseRequired = "PassphraseRequired",
+  _KeyboardInteractivePrompt = "KeyboardInteractivePrompt",
+  _CertificateExpired = "CertificateExpired",
+  _Disconnected = "Disconnected",
+  _Reconnecting = "Reconnecting",
+  _SessionEnded = "SessionEnded",
+  _AgentForwarded = "AgentForwarded",
+  _AgentForwardRejected = "AgentForwardRejected",
 }
 
+export const PUBLIC_KEY_STATUS = [
+  StatusType._NewPublicKeyFound,
+  StatusType._NewJumpHostPublicKeyFound,
+];
+
 export const ERROR_STATUS = [
   StatusType._ConnectionFailed,
   StatusType._AuthFailed,
+  StatusType._HostKeyChanged,
+  StatusType._HostKeyRevoked,
 ];
@@ -36,6 +36,25 @@ export interface TrustPublicKeyEventData {
   trustPublicKey: boolean;
 }
 
+export interface PassphraseEventData {
+  passphrase: string | null;
+}
+
+export interface KeyboardInteractiveAnswersEventData {
+  keyboardInteractiveAnswers: string[] | null;
+}
+
+export interface KeyboardInteractivePrompt {
+  prompt: string;
+  echo: boolean;
+}
+
+export interface KeyboardInteractiveRequest {
+  name: string;
+  instructions: string;
+  prompts: KeyboardInteractivePrompt[];
+}
+
 export const isOutEventData = (
   data: TerminalEvent,
 ): data is { data: OutEventData } => {
@@ -22,7 +22,9 @@ import {
   InEventData,
   isOutEventData,
   isStatusEventData,
-  isTrustPublicKeyEventData,
+  KeyboardInteractiveAnswersEventData,
+  KeyboardInteractiveRequest,
+  PassphraseEventData,
   TerminalEvent,
   TrustPublicKeyEventData,
   WindowChangeEventData,
@@ -37,6 +39,10 @@ export const TerminalView = ({ terminal }: TerminalViewProps) => {
   const [agentOpen, setAgentOpen] = useState<boolean>(false);
   const [statusOpen, setStatusOpen] = useState<boolean>(true);
   const [fingerprint, setFingerprint] = useState<string | null>(null);
+  const [jumpHostId, setJumpHostId] = useState<string | null>(null);
+  const [passphraseLabel, setPassphraseLabel] = useState<string | null>(null);
+  const [keyboardInteractiveRequest, setKeyboardInteractiveRequest] =
+    useState<KeyboardInteractiveRequest | null>(null);
 
   const [xterm, setXterm] = useState<Xterm | undefined>(undefined);
   const fitAddon = useRef<FitAddon>(new FitAddon());
@@ -78,7 +84,12 @@ export const TerminalView = ({ terminal }: TerminalViewProps) => {
     queryFn: async () => {
       if (host) {
         try {
-          return hostService.starTerminalStream(host.id, terminal);
+          return hostService.starTerminalStream(
+            host.id,
+            terminal,
+            xterm?.cols,
+            xterm?.rows
+          );
         } catch (e) {
           console.log(e);
           throw e;
@@ -86,6 +97,7 @@ export const TerminalView = ({ terminal }: TerminalViewProps) => {
       }
       return null;
     },
+    enabled: !!xterm,
     retry: 0,
     refetchInterval: 0,
     refetchOnReconnect: false,
@@ -156,10 +168,6 @@ export const TerminalView = ({ terminal }: TerminalViewProps) => {
         async ({ payload }) => {
           if (isOutEventData(payload)) {
             xterm.write(payload.data.out);
-          } else if (isTrustPublicKeyEventData(payload)) {
-            if (fingerprint) {
-              await hostService.updateFingerprint(host.id, fingerprint);
-            }
           } else if (isStatusEventData(payload)) {
             setStatus(payload.data.status.type);
             if (payload.data.status.type === StatusType._StartStreaming) {
@@ -172,7 +180,27 @@ export const TerminalView = ({ terminal }: TerminalViewProps) => {
             } else if (
               payload.data.status.type === StatusType._NewPublicKeyFound
             ) {
+              setJumpHostId(null);
               setFingerprint(payload.data.status.data as string);
+            } else if (
+              payload.data.status.type ===
+              StatusType._NewJumpHostPublicKeyFound
+            ) {
+              const [hostId, jumpHostFingerprint] = payload.data.status
+                .data as [string, string];
+              setJumpHostId(hostId);
+              setFingerprint(jumpHostFingerprint);
+            } else if (
+              payload.data.status.type === StatusType._PassphraseRequired
+            ) {
+              setPassphraseLabel(payload.data.status.data as string);
+            } else if (
+              payload.data.status.type ===
+              StatusType._KeyboardInteractivePrompt
+            ) {
+              setKeyboardInteractiveRequest(
+                payload.data.status.data as KeyboardInteractiveRequest
+              );
             } else if (ERROR_STATUS.includes(payload.data.status.type)) {
               await futureService.stopFuture(terminal);
             }
@@ -186,7 +214,7 @@ export const TerminalView = ({ terminal }: TerminalViewProps) => {
         unlistenOutFn();
       }
     };
-  }, [xterm, terminal, fingerprint, host.id]);
+  }, [xterm, terminal]);
 
   const handleReconnect = async () => {
     if (!ref) return;
@@ -214,16 +242,39 @@ export const TerminalView = ({ terminal }: TerminalViewProps) => {
 
   const handleConfirmPublicKey = async (confirm: boolean) => {
     if (confirm) {
+      if (fingerprint) {
+        await hostService.updateFingerprint(
+          jumpHostId ?? host.id,
+          fingerprint,
+          terminal
+        );
+      }
+    } else {
       await emit(terminal, {
         data: { trustPublicKey: confirm } as TrustPublicKeyEventData,
       });
-    } else {
       await futureService.stopFuture(terminal);
       removeTerminal(terminal);
       setActiveTerminal(null);
     }
   };
 
+  const handlePassphraseSubmit = async (passphrase: string | null) => {
+    setPassphraseLabel(null);
+    await emit(terminal, {
+      data: { passphrase } as PassphraseEventData,
+    });
+  };
+
+  const handleKeyboardInteractiveSubmit = async (answers: string[] | null) => {
+    setKeyboardInteractiveRequest(null);
+    await emit(terminal, {
+      data: {
+        keyboardInteractiveAnswers: answers,
+      } as KeyboardInteractiveAnswersEventData,
+    });
+  };
+
   return (
     <div ref={setRef} className="h-full">
       <AgentDialog
@@ -233,11 +284,15 @@ export const TerminalView = ({ terminal }: TerminalViewProps) => {
       />
       <StatusDialog
         fingerprint={fingerprint}
+        passphraseLabel={passphraseLabel}
+        keyboardInteractiveRequest={keyboardInteractiveRequest}
         open={statusOpen}
         status={status}
         onReconnect={handleReconnect}
         onClose={handleTerminalClose}
         onPublicKeyConfirm={handleConfirmPublicKey}
+        onPassphraseSubmit={handlePassphraseSubmit}
+        onKeyboardInteractiveSubmit={handleKeyboardInteractiveSubmit}
       />
     </div>
   );